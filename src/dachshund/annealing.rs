@@ -0,0 +1,328 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate rand;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::time::Instant;
+
+use rand::prelude::*;
+
+use crate::dachshund::beam::{BeamSearchResult, SearchBudget, SeedIssue};
+use crate::dachshund::candidate::Candidate;
+use crate::dachshund::error::{CLQError, CLQResult};
+use crate::dachshund::graph::GraphBase;
+use crate::dachshund::id_types::{GraphId, NodeId};
+use crate::dachshund::row::CliqueRow;
+use crate::dachshund::scorer::Scorer;
+use crate::dachshund::trace::{CandidateSummary, TraceEvent, TraceSink};
+
+/// How the temperature decreases over the course of a run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cooling {
+    /// the temperature is multiplied by the given rate (in (0, 1)) at each step.
+    Geometric(f32),
+    /// the temperature decreases linearly, reaching 0 at the last step.
+    Linear,
+}
+
+impl Default for Cooling {
+    fn default() -> Self {
+        Cooling::Geometric(0.99)
+    }
+}
+
+impl FromStr for Cooling {
+    type Err = CLQError;
+    /// parses "linear", or "geometric" with an optional rate (e.g. "geometric:0.95").
+    fn from_str(s: &str) -> CLQResult<Self> {
+        let mut parts = s.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some("linear"), None) => Ok(Cooling::Linear),
+            (Some("geometric"), None) => Ok(Cooling::default()),
+            (Some("geometric"), Some(rate)) => {
+                let rate: f32 = rate.parse::<f32>()?;
+                if !(rate > 0.0 && rate < 1.0) {
+                    return Err(CLQError::from(format!(
+                        "Geometric cooling rate must be in (0, 1), got: {}",
+                        rate
+                    )));
+                }
+                Ok(Cooling::Geometric(rate))
+            }
+            _ => Err(CLQError::from(format!("Unknown cooling schedule: {}", s))),
+        }
+    }
+}
+
+/// The temperature schedule of a simulated annealing search, and the number of runs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnnealingSchedule {
+    /// temperature at the first step of each run.
+    pub initial_temperature: f32,
+    pub cooling: Cooling,
+    /// number of moves proposed per run.
+    pub num_steps: usize,
+    /// number of runs after the first one, each starting from a new candidate.
+    pub num_restarts: usize,
+}
+
+impl Default for AnnealingSchedule {
+    fn default() -> Self {
+        Self {
+            initial_temperature: 1.0,
+            cooling: Cooling::default(),
+            num_steps: 1000,
+            num_restarts: 4,
+        }
+    }
+}
+
+impl AnnealingSchedule {
+    /// the temperature at a given step of a run.
+    pub fn get_temperature(&self, step: usize) -> f32 {
+        match self.cooling {
+            Cooling::Geometric(rate) => self.initial_temperature * rate.powi(step as i32),
+            Cooling::Linear => {
+                self.initial_temperature * (1.0 - step as f32 / self.num_steps as f32)
+            }
+        }
+    }
+}
+
+/// Used for (quasi-)clique detection, as an alternative to `Beam`. Each run starts from a
+/// single candidate, and repeatedly proposes a random move: adding a node adjacent to the
+/// candidate, or removing one of its members. Moves which improve the score are always
+/// accepted, others with a probability which decreases with the temperature. The first runs
+/// start from the seed cliques (if any), the others from a random node.
+pub struct Annealer<'a, TGraph>
+where
+    TGraph: GraphBase,
+{
+    pub graph: &'a TGraph,
+    /// if provided, receives a `SeedIssue` event for each seed clique that could not be used
+    /// as is, an `ExpansionScored` event for each proposed move (with the run as its epoch),
+    /// and an `EarlyStop` event if the budget is exceeded. If not provided, events are
+    /// printed to stderr in verbose mode.
+    pub tracer: Option<&'a dyn TraceSink>,
+    /// whether to print events to stderr, if there is no tracer (defaults to false).
+    pub verbose: bool,
+    /// limits on the resources spent by `run_search`, each proposed move counting as an
+    /// expansion (defaults to no limits).
+    pub budget: SearchBudget,
+    /// seed cliques that could not be used as is, with their clique_id (see
+    /// `Beam::seed_issues`). These get reported when the search starts.
+    pub seed_issues: Vec<(Option<usize>, SeedIssue)>,
+    scorer: Box<dyn Scorer<TGraph> + 'a>,
+    seeds: Vec<Candidate<'a, TGraph>>,
+    non_core_types: &'a [String],
+    graph_id: GraphId,
+    rng: StdRng,
+}
+
+impl<'a, TGraph: GraphBase> Annealer<'a, TGraph> {
+    /// creates a new annealer. Parameters are as per `Beam::new`: seed cliques are built
+    /// from `clique_rows` (one per clique_id), and the pseudorandom sequence is seeded with
+    /// `graph_id` (and `rng_seed`, if provided).
+    pub fn new(
+        graph: &'a TGraph,
+        clique_rows: Vec<CliqueRow>,
+        scorer: Box<dyn Scorer<TGraph> + 'a>,
+        non_core_types: &'a [String],
+        graph_id: GraphId,
+        rng_seed: Option<u64>,
+    ) -> CLQResult<Self> {
+        let mut seeder = DefaultHasher::new();
+        graph_id.hash(&mut seeder);
+        if let Some(seed) = rng_seed {
+            seed.hash(&mut seeder);
+        }
+        let mut seed_cliques: BTreeMap<Option<usize>, Vec<CliqueRow>> = BTreeMap::new();
+        for row in clique_rows {
+            seed_cliques.entry(row.clique_id).or_default().push(row);
+        }
+        let mut seeds: Vec<Candidate<TGraph>> = Vec::new();
+        let mut seed_issues: Vec<(Option<usize>, SeedIssue)> = Vec::new();
+        for (clique_id, rows) in seed_cliques {
+            match Candidate::from_clique_rows(rows, graph, scorer.as_ref())? {
+                Some(seed) => {
+                    if seed.get_score()? <= 0.0 {
+                        seed_issues.push((clique_id, SeedIssue::Invalid));
+                    }
+                    seeds.push(seed);
+                }
+                None => seed_issues.push((clique_id, SeedIssue::NoOverlap)),
+            }
+        }
+        Ok(Self {
+            graph,
+            tracer: None,
+            verbose: false,
+            budget: SearchBudget::default(),
+            seed_issues,
+            scorer,
+            seeds,
+            non_core_types,
+            graph_id,
+            rng: StdRng::seed_from_u64(seeder.finish()),
+        })
+    }
+
+    /// whether the search has exceeded its budget, after proposing `num_steps` moves.
+    fn is_out_of_budget(&self, start: &Instant, num_steps: usize) -> bool {
+        if let Some(time) = self.budget.time {
+            if start.elapsed() >= time {
+                return true;
+            }
+        }
+        if let Some(num_expansions) = self.budget.num_expansions {
+            if num_steps >= num_expansions {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// sends an event to the tracer, or prints it to stderr in verbose mode.
+    fn trace(&self, event: TraceEvent) -> CLQResult<()> {
+        match self.tracer {
+            Some(tracer) => tracer.record(&event)?,
+            None => {
+                if self.verbose {
+                    eprintln!("{}", event.to_json());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// reports seed cliques that could not be used as is (see `trace`).
+    fn report_seed_issues(&self) -> CLQResult<()> {
+        for &(clique_id, issue) in &self.seed_issues {
+            self.trace(TraceEvent::SeedIssue {
+                graph_id: self.graph_id,
+                clique_id,
+                issue: issue.as_str().to_string(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// proposes a random neighbor of `candidate`, or None if it has none.
+    fn propose_move(
+        &mut self,
+        candidate: &Candidate<'a, TGraph>,
+    ) -> CLQResult<Option<Candidate<'a, TGraph>>> {
+        let mut adjacent_ids: Vec<NodeId> = candidate.get_adjacent_nodes()?.into_iter().collect();
+        adjacent_ids.sort();
+        let member_ids: Vec<NodeId> = candidate.get_canonical_ids();
+        // a candidate with a single member can only grow.
+        let can_remove: bool = member_ids.len() > 1;
+        let remove: bool = match (adjacent_ids.is_empty(), can_remove) {
+            (true, false) => return Ok(None),
+            (true, true) => true,
+            (false, false) => false,
+            (false, true) => self.rng.gen::<f32>() < 0.5,
+        };
+        let mut proposal: Candidate<TGraph> = candidate.replicate(false);
+        if remove {
+            let node_id: NodeId = *member_ids.choose(&mut self.rng).ok_or_else(CLQError::err_none)?;
+            proposal.remove_node(node_id)?;
+        } else {
+            let node_id: NodeId =
+                *adjacent_ids.choose(&mut self.rng).ok_or_else(CLQError::err_none)?;
+            proposal.add_node(node_id)?;
+        }
+        let score = self.scorer.score(&proposal)?;
+        proposal.set_score(score)?;
+        Ok(Some(proposal))
+    }
+
+    /// runs the search as per `schedule`, returning the best candidate found over all
+    /// runs. There are at least as many runs as seed cliques. `num_steps` counts the moves
+    /// proposed. If the budget is exceeded, the search stops early, and the result is
+    /// marked as truncated.
+    pub fn run_search(
+        &mut self,
+        schedule: &AnnealingSchedule,
+    ) -> CLQResult<BeamSearchResult<'a, TGraph>> {
+        let start = Instant::now();
+        self.report_seed_issues()?;
+        let mut all_ids: Vec<NodeId> = self.graph.get_core_ids().clone();
+        all_ids.extend(self.graph.get_non_core_ids().ok_or_else(CLQError::err_none)?);
+        let mut best: Option<Candidate<TGraph>> = None;
+        let mut num_steps: usize = 0;
+        let mut stop_reason: Option<&'static str> = None;
+        let num_runs: usize = (schedule.num_restarts + 1).max(self.seeds.len());
+        'runs: for run in 0..num_runs {
+            let mut current: Candidate<TGraph> = match self.seeds.get(run) {
+                Some(seed) => seed.replicate(true),
+                None => {
+                    let node_id: NodeId =
+                        *all_ids.choose(&mut self.rng).ok_or_else(CLQError::err_none)?;
+                    Candidate::new(node_id, self.graph, self.scorer.as_ref())?
+                }
+            };
+            for step in 0..schedule.num_steps {
+                let is_best: bool = match &best {
+                    Some(b) => current.get_score()? > b.get_score()?,
+                    None => true,
+                };
+                if is_best {
+                    best = Some(current.replicate(true));
+                }
+                if self.is_out_of_budget(&start, num_steps) {
+                    self.trace(TraceEvent::EarlyStop {
+                        graph_id: self.graph_id,
+                        epoch: run,
+                        reason: "budget_exceeded".to_string(),
+                    })?;
+                    stop_reason = Some("budget_exceeded");
+                    break 'runs;
+                }
+                let proposal: Candidate<TGraph> = match self.propose_move(&current)? {
+                    Some(p) => p,
+                    None => break,
+                };
+                num_steps += 1;
+                if self.tracer.is_some() {
+                    self.trace(TraceEvent::ExpansionScored {
+                        graph_id: self.graph_id,
+                        epoch: run,
+                        candidate: CandidateSummary::from_candidate(
+                            &proposal,
+                            self.non_core_types,
+                        )?,
+                        components: self.scorer.get_score_components(&proposal)?,
+                    })?;
+                }
+                let delta: f32 = proposal.get_score()? - current.get_score()?;
+                let temperature: f32 = schedule.get_temperature(step);
+                let accept: bool = delta >= 0.0
+                    || (temperature > 0.0 && self.rng.gen::<f32>() < (delta / temperature).exp());
+                if accept {
+                    current = proposal;
+                }
+            }
+            let is_best: bool = match &best {
+                Some(b) => current.get_score()? > b.get_score()?,
+                None => true,
+            };
+            if is_best {
+                best = Some(current);
+            }
+        }
+        Ok(BeamSearchResult {
+            top_candidate: best.ok_or_else(CLQError::err_none)?,
+            num_steps,
+            truncated: stop_reason.is_some(),
+            stop_reason,
+        })
+    }
+}
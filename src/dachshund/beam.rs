@@ -7,17 +7,22 @@
 extern crate rand;
 
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::io::BufRead;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 use rand::prelude::*;
 
-use crate::dachshund::candidate::Candidate;
+use crate::dachshund::candidate::{Candidate, MoveSet, VisitedCandidates};
 use crate::dachshund::error::{CLQError, CLQResult};
 use crate::dachshund::graph::GraphBase;
 use crate::dachshund::id_types::{GraphId, NodeId};
+use crate::dachshund::input::Input;
 use crate::dachshund::row::CliqueRow;
 use crate::dachshund::scorer::Scorer;
+use crate::dachshund::trace::{CandidateSummary, TraceEvent, TraceSink};
 
 /// The result of a beam search.
 pub struct BeamSearchResult<'a, TGraph>
@@ -26,6 +31,116 @@ where
 {
     pub top_candidate: Candidate<'a, TGraph>,
     pub num_steps: usize,
+    /// whether the search was cut short by its budget.
+    pub truncated: bool,
+    /// why the search stopped before running all of its epochs, if it did (e.g.
+    /// "repeated_prior_scores", as in `TraceEvent::EarlyStop`).
+    pub stop_reason: Option<&'static str>,
+}
+
+/// Describes why a seed clique could not be used as is to initialize the beam.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeedIssue {
+    /// none of the seed clique's nodes are in the (pruned) graph, so it was dropped.
+    NoOverlap,
+    /// the seed clique does not meet the cliqueness requirements of the current search
+    /// (i.e., it has a non-positive score). It is still used as a starting point.
+    Invalid,
+}
+
+impl SeedIssue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeedIssue::NoOverlap => "no_overlap",
+            SeedIssue::Invalid => "invalid",
+        }
+    }
+}
+
+/// Per-graph limits on the resources spent by a search. Once either is exceeded, the search
+/// stops gracefully, returning the best candidate found so far.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchBudget {
+    /// maximum (wall-clock) time to spend searching.
+    pub time: Option<Duration>,
+    /// maximum number of expansion candidates to score.
+    pub num_expansions: Option<usize>,
+}
+
+impl SearchBudget {
+    /// whether any limit is set.
+    pub fn is_set(&self) -> bool {
+        self.time.is_some() || self.num_expansions.is_some()
+    }
+}
+
+/// Determines how the beam is initialized, i.e. which nodes the first candidates
+/// consist of (in addition to any seed cliques).
+#[derive(Clone, Debug, PartialEq)]
+pub enum SeedingStrategy {
+    /// picks a core or non-core node with equal probability, then performs a random walk
+    /// of the given length along the graph, starting at that node.
+    RandomWalk(usize),
+    /// picks nodes uniformly at random.
+    UniformRandom,
+    /// picks the nodes with the highest degree.
+    HighestDegree,
+    /// picks nodes from the densest k-core of the graph, highest degree first.
+    DensestCore,
+    /// picks nodes supplied for each graph (e.g. in a file). Falls back to random walks
+    /// for graphs without any supplied nodes.
+    Supplied(HashMap<GraphId, Vec<NodeId>>),
+}
+
+impl Default for SeedingStrategy {
+    fn default() -> Self {
+        SeedingStrategy::RandomWalk(7)
+    }
+}
+
+impl FromStr for SeedingStrategy {
+    type Err = CLQError;
+    /// parses one of "random_walk[:length]", "uniform_random", "highest_degree" and
+    /// "densest_core". Supplied seeds are read with `SeedingStrategy::from_file`.
+    fn from_str(s: &str) -> CLQResult<Self> {
+        let mut parts = s.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some("random_walk"), None) => Ok(SeedingStrategy::default()),
+            (Some("random_walk"), Some(length)) => {
+                Ok(SeedingStrategy::RandomWalk(length.parse::<usize>()?))
+            }
+            (Some("uniform_random"), None) => Ok(SeedingStrategy::UniformRandom),
+            (Some("highest_degree"), None) => Ok(SeedingStrategy::HighestDegree),
+            (Some("densest_core"), None) => Ok(SeedingStrategy::DensestCore),
+            _ => Err(CLQError::from(format!("Unknown seeding strategy: {}", s))),
+        }
+    }
+}
+
+impl SeedingStrategy {
+    /// reads seeds from an input with lines of the form: graph_id\tnode_id.
+    pub fn from_input(input: Input) -> CLQResult<Self> {
+        let mut seeds: HashMap<GraphId, Vec<NodeId>> = HashMap::new();
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let vec: Vec<&str> = line.split('\t').collect();
+            if vec.len() != 2 {
+                return Err(CLQError::from(format!("Malformed seed row: {}", line)));
+            }
+            let graph_id: GraphId = vec[0].parse::<i64>()?.into();
+            let node_id: NodeId = vec[1].trim_end().parse::<i64>()?.into();
+            seeds.entry(graph_id).or_default().push(node_id);
+        }
+        Ok(SeedingStrategy::Supplied(seeds))
+    }
+
+    /// reads seeds from a file (see `from_input`).
+    pub fn from_file(path: &str) -> CLQResult<Self> {
+        SeedingStrategy::from_input(Input::file(path)?)
+    }
 }
 
 /// Used for (quasi-clique) detection. A singleton object that keeps state across the beam search.
@@ -39,8 +154,22 @@ where
     pub graph: &'a TGraph,
     verbose: bool,
     non_core_types: &'a [String],
-    visited_candidates: HashSet<u64>,
-    scorer: Scorer,
+    visited_candidates: VisitedCandidates,
+    scorer: Box<dyn Scorer<TGraph> + 'a>,
+    /// moves considered when expanding candidates (defaults to add moves only).
+    pub move_set: MoveSet,
+    /// receives structured events over the course of the search. If not provided, events
+    /// are printed to stderr in verbose mode.
+    pub tracer: Option<&'a dyn TraceSink>,
+    /// limits on the resources spent by `run_search` (defaults to no limits).
+    pub budget: SearchBudget,
+    /// seed cliques that could not be used as is, with their clique_id. These get
+    /// reported when the search starts.
+    pub seed_issues: Vec<(Option<usize>, SeedIssue)>,
+    graph_id: GraphId,
+    start: Instant,
+    num_expansions: usize,
+    truncated: bool,
 }
 
 impl<'a, TGraph: GraphBase> Beam<'a, TGraph> {
@@ -51,7 +180,7 @@ impl<'a, TGraph: GraphBase> Beam<'a, TGraph> {
         rng: &mut impl Rng,
         graph: &TGraph,
         node: NodeId,
-        length: i16,
+        length: usize,
     ) -> CLQResult<NodeId> {
         let mut current: NodeId = node;
         for _i in 0..length {
@@ -66,23 +195,84 @@ impl<'a, TGraph: GraphBase> Beam<'a, TGraph> {
         Ok(current)
     }
 
+    /// picks a core or non-core node with equal probability, then walks `length` steps
+    /// from it.
+    fn get_random_walk_seed(
+        rng: &mut impl Rng,
+        graph: &TGraph,
+        length: usize,
+        graph_id: GraphId,
+    ) -> CLQResult<NodeId> {
+        let core_ids: &Vec<NodeId> = graph.get_core_ids();
+        let non_core_ids: &Vec<NodeId> = graph.get_non_core_ids().ok_or_else(CLQError::err_none)?;
+        assert!(!core_ids.is_empty());
+        assert!(!non_core_ids.is_empty());
+        let ids_vec = if rng.gen::<f32>() <= 0.5 {
+            &non_core_ids
+        } else {
+            &core_ids
+        };
+        let root_id = ids_vec.choose(rng).ok_or_else(|| {
+            format!("Problem finding root in graph_id: {}", graph_id.value())
+        })?;
+        Beam::random_walk(rng, graph, *root_id, length)
+    }
+
+    /// returns all core and non-core node ids, sorted by descending degree (with node_id
+    /// as tie breaker, for deterministic behaviour).
+    fn get_ids_by_degree(graph: &TGraph) -> CLQResult<Vec<NodeId>> {
+        let mut ids: Vec<NodeId> = graph
+            .get_core_ids()
+            .iter()
+            .chain(graph.get_non_core_ids().ok_or_else(CLQError::err_none)?.iter())
+            .cloned()
+            .collect();
+        ids.sort_by_key(|&id| (std::cmp::Reverse(graph.get_node(id).degree()), id));
+        Ok(ids)
+    }
+
+    /// returns the nodes of the densest k-core of the graph (i.e. those with the highest
+    /// coreness), by iteratively peeling off the node with the lowest degree. Sorted by
+    /// descending degree.
+    fn get_densest_core_ids(graph: &TGraph) -> CLQResult<Vec<NodeId>> {
+        let ids: Vec<NodeId> = Beam::get_ids_by_degree(graph)?;
+        let mut degrees: HashMap<NodeId, usize> = ids
+            .iter()
+            .map(|&id| (id, graph.get_node(id).degree()))
+            .collect();
+        let mut queue: BTreeSet<(usize, NodeId)> =
+            degrees.iter().map(|(&id, &degree)| (degree, id)).collect();
+        let mut coreness: HashMap<NodeId, usize> = HashMap::new();
+        let mut k: usize = 0;
+        while let Some(&(degree, id)) = queue.iter().next() {
+            queue.remove(&(degree, id));
+            degrees.remove(&id);
+            k = k.max(degree);
+            coreness.insert(id, k);
+            for edge in &graph.get_node(id).neighbors {
+                if let Some(neighbor_degree) = degrees.get_mut(&edge.target_id) {
+                    queue.remove(&(*neighbor_degree, edge.target_id));
+                    *neighbor_degree -= 1;
+                    queue.insert((*neighbor_degree, edge.target_id));
+                }
+            }
+        }
+        Ok(ids.into_iter().filter(|id| coreness[id] == k).collect())
+    }
+
     /// creates new beam for mining quasi-bicliques. The following parameters are required:
     ///     - `graph`: a reference to a `TGraph` object (typically constructed by a transformer`.
     ///     - `clique_rows`: a Vector of `CliqueRow` entries, which are used to initialize the
-    ///     search process with already-existing cliques.
+    ///     search process with already-existing cliques (one candidate per clique_id).
     ///     - `beam_size`: the number of top candidates to maintain as potential future sources
     ///     for expansion in the "beam" (i.e., the list of top candidates).
-    ///     - `verbose`: used for debugging.
+    ///     - `verbose`: used for debugging (prints trace events to stderr).
     ///     - `non_core_types`: list of string identifiers for non-core types.
-    ///     - `alpha`: `Scorer` constructor parameter. Controls the contribution of density
-    ///     to the ``cliqueness'' score. Higher values means denser cliques are prefered, all else
-    ///     being equal.
-    ///     - `global_thresh`: `Scorer` constructor parameter. If provided, candidates must be at 
-    ///     least this dense to be considered valid (quasi-)cliques.
-    ///     - `local_thresh`: `Scorer` constructor parameter. if provided, each node in the candidate 
-    ///     must have at least `local_thresh` proportion of ties to other nodes in the candidate,
-    ///     for the candidate to be considered valid.
+    ///     - `scorer`: the `Scorer` used to compute the objective maximized by the search.
     ///     - `graph_id`: uniquely identifies the graph currently being processed.
+    ///     - `seeding_strategy`: determines which nodes the beam is initialized with.
+    ///     - `rng_seed`: if provided, combined with `graph_id` to seed the pseudorandom
+    ///     sequence. Otherwise, the sequence is seeded with `graph_id` only.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         graph: &'a TGraph,
@@ -90,48 +280,79 @@ impl<'a, TGraph: GraphBase> Beam<'a, TGraph> {
         beam_size: usize,
         verbose: bool,
         non_core_types: &'a [String],
-        num_non_core_types: usize,
-        alpha: f32,
-        global_thresh: Option<f32>,
-        local_thresh: Option<f32>,
+        scorer: Box<dyn Scorer<TGraph> + 'a>,
         graph_id: GraphId,
+        seeding_strategy: &SeedingStrategy,
+        rng_seed: Option<u64>,
     ) -> CLQResult<Beam<'a, TGraph>> {
-        let core_ids: &Vec<NodeId> = &graph.get_core_ids();
-        let non_core_ids: &Vec<NodeId> = &graph.get_non_core_ids().unwrap();
-
         let mut candidates: Vec<Candidate<TGraph>> = Vec::new();
-        let scorer: Scorer = Scorer::new(num_non_core_types, alpha, global_thresh, local_thresh);
 
         // To ensure deterministic behaviour between two identically configured runs,
         // seed the pseudorandom sequence with the current cluster.
         let mut seeder = DefaultHasher::new();
         graph_id.hash(&mut seeder);
+        if let Some(seed) = rng_seed {
+            seed.hash(&mut seeder);
+        }
         let mut rng = StdRng::seed_from_u64(seeder.finish());
 
-        if !clique_rows.is_empty() {
-            let init_clique = Candidate::from_clique_rows(clique_rows, graph, &scorer)?;
-            if init_clique != None {
-                candidates.push(init_clique.unwrap());
+        let mut seed_cliques: BTreeMap<Option<usize>, Vec<CliqueRow>> = BTreeMap::new();
+        for row in clique_rows {
+            seed_cliques.entry(row.clique_id).or_default().push(row);
+        }
+        let mut seed_issues: Vec<(Option<usize>, SeedIssue)> = Vec::new();
+        for (clique_id, rows) in seed_cliques {
+            match Candidate::from_clique_rows(rows, graph, scorer.as_ref())? {
+                Some(init_clique) => {
+                    if init_clique.get_score()? <= 0.0 {
+                        seed_issues.push((clique_id, SeedIssue::Invalid));
+                    }
+                    candidates.push(init_clique);
+                }
+                None => seed_issues.push((clique_id, SeedIssue::NoOverlap)),
             }
         }
 
-        while candidates.len() < beam_size {
-            assert!(!core_ids.is_empty());
-            assert!(!non_core_ids.is_empty());
-            let ids_vec = if rng.gen::<f32>() <= 0.5 {
-                &non_core_ids
-            } else {
-                &core_ids
-            };
-            assert!(!ids_vec.is_empty());
-            let root_id = ids_vec.choose(&mut rng).ok_or_else(|| {
-                format!("Problem finding root in graph_id: {}", graph_id.value())
-            })?;
-            let candidate_node = Beam::random_walk(&mut rng, graph, *root_id, 7)?;
-            let candidate = Candidate::new(candidate_node, graph, &scorer)?;
-            candidates.push(candidate);
+        // deterministic strategies yield at most one candidate per seed node.
+        let seed_ids: Option<Vec<NodeId>> = match seeding_strategy {
+            SeedingStrategy::HighestDegree => Some(Beam::get_ids_by_degree(graph)?),
+            SeedingStrategy::DensestCore => Some(Beam::get_densest_core_ids(graph)?),
+            SeedingStrategy::Supplied(seeds) => seeds.get(&graph_id).map(|ids| {
+                ids.iter()
+                    .filter(|&&id| graph.has_node(id))
+                    .cloned()
+                    .collect()
+            }),
+            _ => None,
+        };
+        match seed_ids {
+            Some(ids) if !ids.is_empty() => {
+                for id in ids {
+                    if candidates.len() >= beam_size {
+                        break;
+                    }
+                    candidates.push(Candidate::new(id, graph, scorer.as_ref())?);
+                }
+            }
+            _ => {
+                let all_ids: Vec<NodeId> = Beam::get_ids_by_degree(graph)?;
+                while candidates.len() < beam_size {
+                    let candidate_node: NodeId = match seeding_strategy {
+                        SeedingStrategy::UniformRandom => {
+                            *all_ids.choose(&mut rng).ok_or_else(CLQError::err_none)?
+                        }
+                        SeedingStrategy::RandomWalk(length) => {
+                            Beam::get_random_walk_seed(&mut rng, graph, *length, graph_id)?
+                        }
+                        // fall back to the default random walk if no seeds are available.
+                        _ => Beam::get_random_walk_seed(&mut rng, graph, 7, graph_id)?,
+                    };
+                    let candidate = Candidate::new(candidate_node, graph, scorer.as_ref())?;
+                    candidates.push(candidate);
+                }
+            }
         }
-        let visited_candidates: HashSet<u64> = HashSet::new();
+        let visited_candidates: VisitedCandidates = VisitedCandidates::new();
         let beam: Beam<TGraph> = Beam {
             candidates,
             graph,
@@ -139,59 +360,124 @@ impl<'a, TGraph: GraphBase> Beam<'a, TGraph> {
             non_core_types,
             visited_candidates,
             scorer,
+            move_set: MoveSet::default(),
+            tracer: None,
+            budget: SearchBudget::default(),
+            seed_issues,
+            graph_id,
+            start: Instant::now(),
+            num_expansions: 0,
+            truncated: false,
         };
         Ok(beam)
     }
 
-    /// Try expanding each member of the beam and keep the top candidates.
+    /// whether the search has exceeded its budget.
+    fn is_out_of_budget(&self) -> bool {
+        if let Some(time) = self.budget.time {
+            if self.start.elapsed() >= time {
+                return true;
+            }
+        }
+        if let Some(num_expansions) = self.budget.num_expansions {
+            if self.num_expansions >= num_expansions {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// whether trace events are being recorded (or printed).
+    fn is_tracing(&self) -> bool {
+        self.tracer.is_some() || self.verbose
+    }
+
+    /// sends an event to the tracer, or prints it to stderr in verbose mode.
+    fn trace(&self, event: TraceEvent) -> CLQResult<()> {
+        match self.tracer {
+            Some(tracer) => tracer.record(&event)?,
+            None => {
+                if self.verbose {
+                    eprintln!("{}", event.to_json());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// reports seed cliques that could not be used as is, to the tracer if there is one,
+    /// and to stderr in verbose mode.
+    fn report_seed_issues(&self) -> CLQResult<()> {
+        for &(clique_id, issue) in &self.seed_issues {
+            self.trace(TraceEvent::SeedIssue {
+                graph_id: self.graph_id,
+                clique_id,
+                issue: issue.as_str().to_string(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Try expanding each member of the beam and keep the top candidates. Once the budget
+    /// is exceeded, remaining members are kept as they are, without being expanded.
     fn one_step_search(
         &mut self,
         num_to_search: usize,
         beam_size: usize,
+        epoch: usize,
     ) -> CLQResult<(Candidate<'a, TGraph>, bool)> {
         let mut scored_expansion_candidates: HashSet<Candidate<TGraph>> = HashSet::new();
         let mut new_candidates: Vec<Candidate<TGraph>> = Vec::new();
         let mut can_continue: bool = false;
+        let mut truncated: bool = false;
+        let is_tracing: bool = self.is_tracing();
+        if is_tracing {
+            self.trace(TraceEvent::EpochStart {
+                graph_id: self.graph_id,
+                epoch,
+                beam_size: self.candidates.len(),
+            })?;
+        }
 
         for candidate in &self.candidates {
-            if self.verbose {
-                eprintln!(
-                    "Considering the following candidate (score = {}, hash={}):\n{}",
-                    match candidate.get_score() {
-                        Ok(n) => n.to_string(),
-                        Err(_) => "No score".to_string(),
-                    },
-                    candidate,
-                    candidate.to_printable_row(self.non_core_types)?,
-                );
+            let visited: bool = self.visited_candidates.contains(candidate);
+            if is_tracing {
+                self.trace(TraceEvent::CandidateConsidered {
+                    graph_id: self.graph_id,
+                    epoch,
+                    candidate: CandidateSummary::from_candidate(candidate, self.non_core_types)?,
+                    visited,
+                })?;
             }
-            if !self
-                .visited_candidates
-                .contains(&candidate.checksum.unwrap())
-            {
+            if !visited && self.is_out_of_budget() {
+                truncated = true;
+            } else if !visited {
                 can_continue = true;
                 let v: Vec<Candidate<TGraph>> = candidate.one_step_search(
                     num_to_search,
                     &mut self.visited_candidates,
-                    &self.scorer,
+                    self.scorer.as_ref(),
+                    &self.move_set,
                 )?;
-                if self.verbose {
-                    eprintln!("Have {} visited candidates:", self.visited_candidates.len());
-                    eprintln!("Found the following expansion candidates:");
-                }
+                self.num_expansions += v.len();
                 for ell in v {
-                    if self.verbose {
-                        eprintln!(
-                            "(score = {}): {}",
-                            ell.get_score()?,
-                            ell.to_printable_row(self.non_core_types)?,
-                        );
+                    if is_tracing {
+                        self.trace(TraceEvent::ExpansionScored {
+                            graph_id: self.graph_id,
+                            epoch,
+                            candidate: CandidateSummary::from_candidate(
+                                &ell,
+                                self.non_core_types,
+                            )?,
+                            components: self.scorer.get_score_components(&ell)?,
+                        })?;
                     }
                     scored_expansion_candidates.insert(ell);
                 }
             }
             scored_expansion_candidates.insert(candidate.replicate(true));
         }
+        self.truncated |= truncated;
 
         // sort by score, with node_id as tie breaker for deterministic behaviour
         let mut v: Vec<Candidate<TGraph>> = scored_expansion_candidates.into_iter().collect();
@@ -214,15 +500,23 @@ impl<'a, TGraph: GraphBase> Beam<'a, TGraph> {
             ));
         }
 
-        if self.verbose {
-            eprintln!("Beam now contains:");
-        }
         for ell in v {
             if new_candidates.len() < beam_size {
                 new_candidates.push(ell);
             }
         }
         self.candidates = new_candidates;
+        if is_tracing {
+            self.trace(TraceEvent::BeamContents {
+                graph_id: self.graph_id,
+                epoch,
+                candidates: self
+                    .candidates
+                    .iter()
+                    .map(|x| CandidateSummary::from_candidate(x, self.non_core_types))
+                    .collect::<CLQResult<Vec<CandidateSummary>>>()?,
+            })?;
+        }
         Ok((self.candidates[0].replicate(true), can_continue))
     }
 
@@ -232,6 +526,8 @@ impl<'a, TGraph: GraphBase> Beam<'a, TGraph> {
     /// score resulting from a one step search is repeated `max_repeated_prior_scores` 
     /// times, the search is terminated early. (Note that the search has a stochastic
     /// component, which is why repeating the search may yield different results).
+    /// The search is also terminated early if it exceeds `self.budget`, in which case
+    /// the result is marked as truncated.
     pub fn run_search(
         &mut self,
         num_to_search: usize,
@@ -242,41 +538,74 @@ impl<'a, TGraph: GraphBase> Beam<'a, TGraph> {
         let mut prior_score: f32 = -2.0;
         let mut num_repeated_prior_scores: usize = 0;
         let mut num_steps: usize = 0;
+        let mut stop_reason: Option<&'static str> = None;
+        self.start = Instant::now();
+        self.report_seed_issues()?;
         if num_epochs > 0 {
+            // set if the budget is exceeded before the final step, which is then skipped.
+            let mut truncated_top: Option<Candidate<TGraph>> = None;
             for i in 0..num_epochs - 1 {
                 num_steps = i + 1;
                 let (top, can_continue): (Candidate<TGraph>, bool) =
-                    self.one_step_search(num_to_search, beam_size)?;
+                    self.one_step_search(num_to_search, beam_size, i)?;
+                if self.truncated {
+                    self.trace(TraceEvent::EarlyStop {
+                        graph_id: self.graph_id,
+                        epoch: i,
+                        reason: "budget_exceeded".to_string(),
+                    })?;
+                    stop_reason = Some("budget_exceeded");
+                    truncated_top = Some(top);
+                    break;
+                }
                 // result of all candidates being previously visited
                 if !can_continue {
+                    self.trace(TraceEvent::EarlyStop {
+                        graph_id: self.graph_id,
+                        epoch: i,
+                        reason: "all_candidates_visited".to_string(),
+                    })?;
+                    stop_reason = Some("all_candidates_visited");
                     break;
                 }
                 let score: f32 = top.get_score()?;
-                if self.verbose {
-                    eprintln!(
-                        "Top candidate found: (score = {}): {}",
-                        score,
-                        top.to_printable_row(self.non_core_types)?,
-                    );
-                }
                 assert!(score >= prior_score);
-                if self.verbose {
-                    eprintln!("Score: {}, prior score: {}", score, prior_score);
-                }
                 if (score - prior_score).abs() <= f32::EPSILON {
                     num_repeated_prior_scores += 1;
                 } else {
                     num_repeated_prior_scores = 0;
                 }
                 if num_repeated_prior_scores == max_repeated_prior_scores {
+                    self.trace(TraceEvent::EarlyStop {
+                        graph_id: self.graph_id,
+                        epoch: i,
+                        reason: "repeated_prior_scores".to_string(),
+                    })?;
+                    stop_reason = Some("repeated_prior_scores");
                     break;
                 }
                 prior_score = score;
             }
-            let result = self.one_step_search(num_to_search, beam_size)?;
+            let top_candidate: Candidate<TGraph> = match truncated_top {
+                Some(top) => top,
+                None => {
+                    let (top, _) = self.one_step_search(num_to_search, beam_size, num_steps)?;
+                    if self.truncated {
+                        self.trace(TraceEvent::EarlyStop {
+                            graph_id: self.graph_id,
+                            epoch: num_steps,
+                            reason: "budget_exceeded".to_string(),
+                        })?;
+                        stop_reason = Some("budget_exceeded");
+                    }
+                    top
+                }
+            };
             return Ok(BeamSearchResult {
-                top_candidate: result.0,
+                top_candidate,
                 num_steps,
+                truncated: self.truncated,
+                stop_reason,
             });
         }
         // if we're just running for 0 epochs (for debug purposes, return top candidate)
@@ -292,6 +621,8 @@ impl<'a, TGraph: GraphBase> Beam<'a, TGraph> {
         Ok(BeamSearchResult::<TGraph> {
             top_candidate: best_candidate,
             num_steps: 0,
+            truncated: false,
+            stop_reason: None,
         })
     }
 }
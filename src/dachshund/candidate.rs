@@ -5,23 +5,27 @@
  * LICENSE file in the root directory of this source tree.
  */
 extern crate rustc_serialize;
+extern crate serde_json;
 
 use std::cmp::Reverse;
 use std::cmp::{Eq, PartialEq};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 use rustc_serialize::json;
+use serde_json::{Map, Value};
 
 use crate::dachshund::error::{CLQError, CLQResult};
 use crate::dachshund::graph::GraphBase;
-use crate::dachshund::id_types::{GraphId, NodeId, NodeTypeId};
+use crate::dachshund::id_types::{EdgeTypeId, GraphId, NodeId, NodeTypeId};
+use crate::dachshund::interner::NodeIdInterner;
 use crate::dachshund::node::Node;
-use crate::dachshund::output::Output;
+use crate::dachshund::output::{json_f32, Output};
 use crate::dachshund::row::CliqueRow;
-use crate::dachshund::scorer::Scorer;
+use crate::dachshund::scorer::{Scorer, SizeConstraints};
 
 /// This data structure contains everything that identifies a candidate (fuzzy) clique. To
 /// reiterate, a (fuzzy) clique is a subgraph of edges going from some set of "core" nodes
@@ -32,7 +36,18 @@ use crate::dachshund::scorer::Scorer;
 ///
 /// The struct keeps state in two `HashSets`, of core and non_core node ids. There's also a
 /// convenience reference to `Graph`, a checksum summarising the full state, and a field
-/// in which to maintain the candidate's current score.
+/// in which to maintain the candidate's current score. The checksum is only a fingerprint:
+/// two candidates are equal if they have the same members (and score), and different
+/// candidates may (rarely) share a checksum.
+///
+/// Tie counts are maintained incrementally as nodes get added: for each member, the
+/// (weighted) number of ties it has with members on the other side, as well as the
+/// total number (and weight) of ties, and the sum of maximum edge counts over non-core
+/// members. The weight of ties between non-core members (which only exist if the typespec
+/// has relations between non-core types), and the number of non-core members of each
+/// type, are maintained as well, and so is the time span of timestamped ties between
+/// members (which is only recomputed when removing a node whose ties bound it). This way,
+/// scoring a candidate does not require rescanning its members' edges.
 ///
 /// Note that in the current implementation, ``core'' ids must all be of the same type,
 /// whereas non-core ids can be of any type is desired.
@@ -45,6 +60,13 @@ where
     pub non_core_ids: HashSet<NodeId>,
     pub checksum: Option<u64>,
     score: Option<f32>,
+    tie_weights: HashMap<NodeId, f32>,
+    num_ties: usize,
+    total_tie_weight: f32,
+    max_core_edge_count: usize,
+    non_core_tie_weight: f32,
+    non_core_type_counts: HashMap<NodeTypeId, usize>,
+    time_span: Option<(i64, i64)>,
 }
 impl<'a, T: GraphBase> Hash for Candidate<'a, T> {
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -52,8 +74,13 @@ impl<'a, T: GraphBase> Hash for Candidate<'a, T> {
     }
 }
 impl<'a, T: GraphBase> PartialEq for Candidate<'a, T> {
+    // the checksum comparison short-circuits the (more expensive) member set comparison
+    // in the vast majority of cases.
     fn eq(&self, other: &Self) -> bool {
-        self.checksum == other.checksum && self.score == other.score
+        self.checksum == other.checksum
+            && self.score == other.score
+            && self.core_ids == other.core_ids
+            && self.non_core_ids == other.non_core_ids
     }
 }
 impl<'a, T: GraphBase> Eq for Candidate<'a, T> {}
@@ -63,6 +90,106 @@ impl<'a, T: GraphBase> fmt::Display for Candidate<'a, T> {
     }
 }
 
+/// The kinds of moves considered when looking for a candidate's neighbours in the search
+/// space. Adding an adjacent node is the classic (greedy) expansion move. Removing the
+/// weakest member, or swapping it for an adjacent node, allows the search to undo poor
+/// early choices.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoveSet {
+    pub add: bool,
+    pub remove: bool,
+    pub swap: bool,
+}
+
+impl MoveSet {
+    pub fn all() -> Self {
+        Self {
+            add: true,
+            remove: true,
+            swap: true,
+        }
+    }
+}
+
+impl Default for MoveSet {
+    /// only add moves, i.e. a greedy expansion.
+    fn default() -> Self {
+        Self {
+            add: true,
+            remove: false,
+            swap: false,
+        }
+    }
+}
+
+impl FromStr for MoveSet {
+    type Err = CLQError;
+    /// parses a comma-separated list of moves, e.g. "add,remove,swap".
+    fn from_str(s: &str) -> CLQResult<Self> {
+        let mut move_set = Self {
+            add: false,
+            remove: false,
+            swap: false,
+        };
+        for move_str in s.split(',') {
+            match move_str.trim() {
+                "add" => move_set.add = true,
+                "remove" => move_set.remove = true,
+                "swap" => move_set.swap = true,
+                _ => return Err(CLQError::from(format!("Unknown move: {}", move_str))),
+            }
+        }
+        Ok(move_set)
+    }
+}
+
+/// Keeps track of the candidates that have already been expanded during a search. Candidates
+/// are looked up by checksum, and then by their canonical (sorted) list of members, which is
+/// only computed when checksums match, so that collisions are resolved correctly.
+#[derive(Default)]
+pub struct VisitedCandidates {
+    data: HashMap<u64, Vec<Vec<NodeId>>>,
+    num_visited: usize,
+}
+
+impl VisitedCandidates {
+    pub fn new() -> Self {
+        Self {
+            data: HashMap::new(),
+            num_visited: 0,
+        }
+    }
+
+    /// whether a candidate with the same members has already been visited.
+    pub fn contains<TGraph: GraphBase>(&self, candidate: &Candidate<TGraph>) -> bool {
+        match self.data.get(&candidate.checksum.unwrap()) {
+            Some(members) => members.contains(&candidate.get_canonical_ids()),
+            None => false,
+        }
+    }
+
+    /// marks a candidate as visited. Returns false if it had already been visited.
+    pub fn insert<TGraph: GraphBase>(&mut self, candidate: &Candidate<TGraph>) -> bool {
+        let canonical_ids: Vec<NodeId> = candidate.get_canonical_ids();
+        let members = self.data.entry(candidate.checksum.unwrap()).or_default();
+        if members.contains(&canonical_ids) {
+            return false;
+        }
+        members.push(canonical_ids);
+        self.num_visited += 1;
+        true
+    }
+
+    /// number of visited candidates.
+    pub fn len(&self) -> usize {
+        self.num_visited
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_visited == 0
+    }
+}
+
 impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
 
     /// creates an empty candidate object, refering to a graph.
@@ -73,11 +200,22 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
             non_core_ids: HashSet::new(),
             checksum: None,
             score: None,
+            tie_weights: HashMap::new(),
+            num_ties: 0,
+            total_tie_weight: 0.0,
+            max_core_edge_count: 0,
+            non_core_tie_weight: 0.0,
+            non_core_type_counts: HashMap::new(),
+            time_span: None,
         }
     }
 
     /// creates a Candidate object from a single node ID.
-    pub fn new(node_id: NodeId, graph: &'a TGraph, scorer: &Scorer) -> CLQResult<Self> {
+    pub fn new(
+        node_id: NodeId,
+        graph: &'a TGraph,
+        scorer: &dyn Scorer<TGraph>,
+    ) -> CLQResult<Self> {
         let mut candidate: Self = Candidate::init_blank(graph);
         candidate.add_node(node_id)?;
         let score = scorer.score(&candidate)?;
@@ -85,16 +223,16 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
         Ok(candidate)
     }
 
-    /// creates a Candidate object from an array of CliqueRows.
+    /// creates a Candidate object from an array of CliqueRows (repeated rows are ignored).
     pub fn from_clique_rows(
         rows: Vec<CliqueRow>,
         graph: &'a TGraph,
-        scorer: &Scorer,
+        scorer: &dyn Scorer<TGraph>,
     ) -> CLQResult<Option<Self>> {
         assert!(!rows.is_empty());
         let mut candidate: Candidate<TGraph> = Candidate::init_blank(graph);
         for row in &rows {
-            if graph.has_node(row.node_id) {
+            if graph.has_node(row.node_id) && !candidate.is_member(row.node_id) {
                 let node: &Node = graph.get_node(row.node_id);
                 assert_eq!(node.non_core_type, row.target_type);
                 candidate.add_node(node.node_id)?;
@@ -109,9 +247,20 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
         Ok(Some(candidate))
     }
 
+    /// whether `node_id` is a (core or non-core) member of the clique.
+    pub fn is_member(&self, node_id: NodeId) -> bool {
+        self.core_ids.contains(&node_id) || self.non_core_ids.contains(&node_id)
+    }
+
     /// add node to the clique -- this results in the score being reset, and the
-    /// clique checksum being changed.
+    /// clique checksum being changed. Tie counts are updated in O(degree) time.
     pub fn add_node(&mut self, node_id: NodeId) -> CLQResult<()> {
+        if self.is_member(node_id) {
+            return Err(CLQError::from(format!(
+                "Node {} is already a member of the candidate.",
+                node_id.value()
+            )));
+        }
         let mut s = DefaultHasher::new();
         node_id.hash(&mut s);
         let node_hash: u64 = s.finish();
@@ -120,15 +269,139 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
         } else {
             self.checksum = Some(node_hash);
         }
-        if self.graph.get_node(node_id).is_core() {
+        let node: &Node = self.graph.get_node(node_id);
+        let mut node_tie_weight: f32 = 0.0;
+        for edge in &node.neighbors {
+            let is_tie_with_member: bool = match node.is_core() {
+                true => self.non_core_ids.contains(&edge.target_id),
+                false => self.core_ids.contains(&edge.target_id),
+            };
+            if is_tie_with_member {
+                *self
+                    .tie_weights
+                    .get_mut(&edge.target_id)
+                    .ok_or_else(CLQError::err_none)? += edge.weight;
+                node_tie_weight += edge.weight;
+                self.num_ties += 1;
+                self.total_tie_weight += edge.weight;
+                self.extend_time_span(edge.timestamp);
+            } else if !node.is_core() && self.non_core_ids.contains(&edge.target_id) {
+                self.non_core_tie_weight += edge.weight;
+                self.extend_time_span(edge.timestamp);
+            }
+        }
+        self.tie_weights.insert(node_id, node_tie_weight);
+        if node.is_core() {
             self.core_ids.insert(node_id);
         } else {
+            // non-core types only tied to other non-core types have no ties with core nodes.
+            self.max_core_edge_count += node.max_edge_count_with_core_node()?.unwrap_or(0);
+            let non_core_type: NodeTypeId = node.non_core_type.ok_or_else(CLQError::err_none)?;
+            *self.non_core_type_counts.entry(non_core_type).or_default() += 1;
             self.non_core_ids.insert(node_id);
         }
         self.reset_score();
         Ok(())
     }
 
+    /// remove node from the clique -- the inverse of `add_node`. Tie counts are updated in
+    /// O(degree) time.
+    pub fn remove_node(&mut self, node_id: NodeId) -> CLQResult<()> {
+        let node: &Node = self.graph.get_node(node_id);
+        let was_member: bool = match node.is_core() {
+            true => self.core_ids.remove(&node_id),
+            false => self.non_core_ids.remove(&node_id),
+        };
+        if !was_member {
+            return Err(CLQError::from(format!(
+                "Node {} is not a member of the candidate.",
+                node_id.value()
+            )));
+        }
+        let mut s = DefaultHasher::new();
+        node_id.hash(&mut s);
+        let node_hash: u64 = s.finish();
+        self.checksum = match self.core_ids.is_empty() && self.non_core_ids.is_empty() {
+            true => None,
+            false => Some(self.checksum.unwrap().wrapping_sub(node_hash)),
+        };
+        let mut is_time_span_bound_removed: bool = false;
+        for edge in &node.neighbors {
+            let is_tie_with_member: bool = match node.is_core() {
+                true => self.non_core_ids.contains(&edge.target_id),
+                false => self.core_ids.contains(&edge.target_id),
+            };
+            if is_tie_with_member {
+                *self
+                    .tie_weights
+                    .get_mut(&edge.target_id)
+                    .ok_or_else(CLQError::err_none)? -= edge.weight;
+                self.num_ties -= 1;
+                self.total_tie_weight -= edge.weight;
+            } else if !node.is_core() && self.non_core_ids.contains(&edge.target_id) {
+                self.non_core_tie_weight -= edge.weight;
+            } else {
+                continue;
+            }
+            if let (Some(t), Some((first, last))) = (edge.timestamp, self.time_span) {
+                is_time_span_bound_removed |= t == first || t == last;
+            }
+        }
+        if is_time_span_bound_removed {
+            self.time_span = self.compute_time_span();
+        }
+        self.tie_weights.remove(&node_id);
+        if !node.is_core() {
+            self.max_core_edge_count -= node.max_edge_count_with_core_node()?.unwrap_or(0);
+            let non_core_type: NodeTypeId = node.non_core_type.ok_or_else(CLQError::err_none)?;
+            *self
+                .non_core_type_counts
+                .get_mut(&non_core_type)
+                .ok_or_else(CLQError::err_none)? -= 1;
+        }
+        self.reset_score();
+        Ok(())
+    }
+
+    /// widens the time span of the candidate to include `timestamp` (if any).
+    fn extend_time_span(&mut self, timestamp: Option<i64>) {
+        if let Some(t) = timestamp {
+            self.time_span = match self.time_span {
+                Some((first, last)) => Some((first.min(t), last.max(t))),
+                None => Some((t, t)),
+            };
+        }
+    }
+
+    /// computes the time span of the candidate from scratch, by scanning the ties of core
+    /// members with non-core members, and the ties between non-core members.
+    fn compute_time_span(&self) -> Option<(i64, i64)> {
+        let mut timestamps: Vec<i64> = Vec::new();
+        for &node_id in self.core_ids.iter().chain(self.non_core_ids.iter()) {
+            for edge in &self.graph.get_node(node_id).neighbors {
+                if self.non_core_ids.contains(&edge.target_id) {
+                    timestamps.extend(edge.timestamp);
+                }
+            }
+        }
+        match (timestamps.iter().min(), timestamps.iter().max()) {
+            (Some(&first), Some(&last)) => Some((first, last)),
+            _ => None,
+        }
+    }
+
+    /// returns the times of the first and last ties between members of the candidate,
+    /// if any of these ties are timestamped.
+    pub fn get_time_span(&self) -> Option<(i64, i64)> {
+        self.time_span
+    }
+
+    /// returns the number of non-core members of the given type (kept up to date as
+    /// members are added and removed).
+    pub fn count_non_core_type(&self, non_core_type: NodeTypeId) -> usize {
+        *self.non_core_type_counts.get(&non_core_type).unwrap_or(&0)
+    }
+
     /// returns sorted vector of core IDs -- useful for printing
     pub fn sorted_core_ids(&self) -> Vec<NodeId> {
         let mut vec: Vec<NodeId> = self.core_ids.iter().cloned().collect();
@@ -143,6 +416,18 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
         vec
     }
 
+    /// returns sorted vector of all member IDs, which uniquely identifies the candidate.
+    pub fn get_canonical_ids(&self) -> Vec<NodeId> {
+        let mut vec: Vec<NodeId> = self
+            .core_ids
+            .iter()
+            .chain(self.non_core_ids.iter())
+            .cloned()
+            .collect();
+        vec.sort();
+        vec
+    }
+
     /// sets score, as computed by a Scorer class.
     pub fn set_score(&mut self, score: f32) -> CLQResult<()> {
         if self.score.is_some() {
@@ -165,6 +450,56 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
         self.graph.get_node(node_id)
     }
 
+    /// returns the (weighted) number of ties a member has with members on the other side
+    /// (i.e. with non-core members for a core node, and vice-versa).
+    pub fn get_member_tie_weight(&self, node_id: NodeId) -> CLQResult<f32> {
+        let tie_weight = self.tie_weights.get(&node_id).ok_or_else(|| {
+            CLQError::from(format!(
+                "Node {} is not a member of the candidate.",
+                node_id.value()
+            ))
+        })?;
+        Ok(*tie_weight)
+    }
+
+    /// returns the proportion of a member's possible ties with members on the other side
+    /// which actually exist (weighted).
+    pub fn get_member_tie_ratio(&self, node_id: NodeId) -> CLQResult<f32> {
+        let node: &Node = self.get_node(node_id);
+        let max_ties: usize = match node.is_core() {
+            true => self.max_core_edge_count,
+            false => self.core_ids.len() * node.max_edge_count_with_core_node()?.unwrap_or(0),
+        };
+        let tie_weight: f32 = self.get_member_tie_weight(node_id)?;
+        Ok(match max_ties {
+            0 => 0.0,
+            _ => tie_weight / max_ties as f32,
+        })
+    }
+
+    /// returns the member with the lowest tie ratio (with node_id as tie breaker, for
+    /// deterministic behaviour), unless the candidate has fewer than two members.
+    pub fn get_weakest_member(&self) -> CLQResult<Option<NodeId>> {
+        if self.core_ids.len() + self.non_core_ids.len() < 2 {
+            return Ok(None);
+        }
+        let mut weakest: Option<(f32, NodeId)> = None;
+        for node_id in self.get_canonical_ids() {
+            let ratio: f32 = self.get_member_tie_ratio(node_id)?;
+            weakest = match weakest {
+                Some((weakest_ratio, _)) if weakest_ratio <= ratio => weakest,
+                _ => Some((ratio, node_id)),
+            };
+        }
+        Ok(weakest.map(|(_, node_id)| node_id))
+    }
+
+    /// returns the maximum number of ties a core node could have with the candidate's
+    /// non-core members.
+    pub fn get_max_core_edge_count(&self) -> usize {
+        self.max_core_edge_count
+    }
+
     /// obtains cliqueness score (higher means ``better'' quality clique, however defined)
     pub fn get_score(&self) -> CLQResult<f32> {
         let score = self
@@ -175,41 +510,122 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
 
     /// encodes self as tab-separated "wide" format
     pub fn to_printable_row(&self, target_types: &[String]) -> CLQResult<String> {
+        self.to_printable_row_with_interner(target_types, None)
+    }
+
+    /// same as `to_printable_row`, but node ids are encoded as the strings they were
+    /// interned from in graph `graph_id`, if an interner is provided.
+    pub fn to_printable_row_with_interner(
+        &self,
+        target_types: &[String],
+        interner: Option<(&NodeIdInterner, GraphId)>,
+    ) -> CLQResult<String> {
+        let fields: Vec<String> = self
+            .get_printable_fields(target_types, interner)?
+            .into_iter()
+            .map(|(_, field)| field)
+            .collect();
+        Ok(fields.join("\t"))
+    }
+
+    /// encodes self as a JSON object, with the same fields as the "wide" format, keyed by
+    /// name (e.g. "core_ids" or "cliqueness").
+    pub fn to_json_with_interner(
+        &self,
+        target_types: &[String],
+        interner: Option<(&NodeIdInterner, GraphId)>,
+    ) -> CLQResult<Map<String, Value>> {
+        let core_ids: Vec<NodeId> = self.sorted_core_ids();
+        let non_core_ids: Vec<NodeId> = self.sorted_non_core_ids();
+        let mut object: Map<String, Value> = Map::new();
+        object.insert("num_core_nodes".into(), Value::from(core_ids.len()));
+        object.insert("num_non_core_nodes".into(), Value::from(non_core_ids.len()));
+        object.insert("core_ids".into(), Self::ids_to_json(&core_ids, interner)?);
+        object.insert("non_core_ids".into(), Self::ids_to_json(&non_core_ids, interner)?);
+        object.insert(
+            "non_core_types".into(),
+            Value::from(self.get_non_core_type_names(target_types)),
+        );
+        object.insert("cliqueness".into(), json_f32(self.get_cliqueness()?));
+        object.insert(
+            "core_densities".into(),
+            self.get_core_densities()?.into_iter().map(json_f32).collect(),
+        );
+        object.insert(
+            "non_core_densities".into(),
+            self.get_non_core_densities(target_types.len())?
+                .into_iter()
+                .map(json_f32)
+                .collect(),
+        );
+        Ok(object)
+    }
+
+    /// the fields of the "wide" format, with their names.
+    fn get_printable_fields(
+        &self,
+        target_types: &[String],
+        interner: Option<(&NodeIdInterner, GraphId)>,
+    ) -> CLQResult<Vec<(&'static str, String)>> {
         let encode_err_handler = |e: json::EncoderError| Err(CLQError::from(e.to_string()));
 
         let cliqueness = self.get_cliqueness()?;
-        let core_ids: Vec<i64> = self.sorted_core_ids().iter().map(|x| x.value()).collect();
-        let non_core_ids: Vec<i64> = self.sorted_non_core_ids().iter().map(|x| x.value()).collect();
+        let core_ids: Vec<NodeId> = self.sorted_core_ids();
+        let non_core_ids: Vec<NodeId> = self.sorted_non_core_ids();
 
-        let mut s = String::new();
-        s.push_str(&core_ids.len().to_string());
-        s.push_str("\t");
-        s.push_str(&non_core_ids.len().to_string());
-        s.push_str("\t");
+        let non_core_types_str: Vec<String> = self.get_non_core_type_names(target_types);
+        Ok(vec![
+            ("num_core_nodes", core_ids.len().to_string()),
+            ("num_non_core_nodes", non_core_ids.len().to_string()),
+            ("core_ids", Self::encode_ids(&core_ids, interner)?),
+            ("non_core_ids", Self::encode_ids(&non_core_ids, interner)?),
+            (
+                "non_core_types",
+                json::encode(&non_core_types_str).or_else(encode_err_handler)?,
+            ),
+            ("cliqueness", cliqueness.to_string()),
+            (
+                "core_densities",
+                json::encode(&self.get_core_densities()?).or_else(encode_err_handler)?,
+            ),
+            (
+                "non_core_densities",
+                json::encode(&self.get_non_core_densities(target_types.len())?)
+                    .or_else(encode_err_handler)?,
+            ),
+        ])
+    }
 
-        s.push_str(&json::encode(&core_ids).or_else(encode_err_handler)?);
-        s.push_str("\t");
+    /// the types of the non-core members, in no particular order.
+    fn get_non_core_type_names(&self, target_types: &[String]) -> Vec<String> {
+        self.non_core_ids
+            .iter()
+            .map(|&id| target_types[self.get_node(id).non_core_type.unwrap().value() - 1].clone())
+            .collect()
+    }
 
-        s.push_str(&json::encode(&non_core_ids).or_else(encode_err_handler)?);
-        s.push_str("\t");
+    /// encodes node ids as a JSON list, of the strings they were interned from (if an
+    /// interner is provided), or of integers.
+    fn encode_ids(
+        ids: &[NodeId],
+        interner: Option<(&NodeIdInterner, GraphId)>,
+    ) -> CLQResult<String> {
+        Ok(Self::ids_to_json(ids, interner)?.to_string())
+    }
 
-        let non_core_types_str: Vec<String> = self
-            .non_core_ids
-            .clone()
-            .into_iter()
-            .map(|id| target_types[self.get_node(id).non_core_type.unwrap().value() - 1].clone())
-            .collect();
-        s.push_str(&json::encode(&non_core_types_str).or_else(encode_err_handler)?);
-        s.push_str("\t");
-        s.push_str(&cliqueness.to_string());
-        s.push_str("\t");
-        s.push_str(&json::encode(&self.get_core_densities()).or_else(encode_err_handler)?);
-        s.push_str("\t");
-        s.push_str(
-            &json::encode(&self.get_non_core_densities(target_types.len())?)
-                .or_else(encode_err_handler)?,
-        );
-        Ok(s)
+    /// same as `encode_ids`, but as a JSON value, for JSON Lines output.
+    fn ids_to_json(
+        ids: &[NodeId],
+        interner: Option<(&NodeIdInterner, GraphId)>,
+    ) -> CLQResult<Value> {
+        Ok(match interner {
+            Some((interner, graph_id)) => Value::from(
+                ids.iter()
+                    .map(|&id| interner.get_original_id(graph_id, id))
+                    .collect::<CLQResult<Vec<String>>>()?,
+            ),
+            None => Value::from(ids.iter().map(|x| x.value()).collect::<Vec<i64>>()),
+        })
     }
 
     /// used for interaction with Transformer classes.
@@ -221,6 +637,7 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
                 graph_id,
                 node_id: core_id,
                 target_type: None,
+                clique_id: None,
             };
             out.push(row);
         }
@@ -231,20 +648,53 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
                 graph_id,
                 node_id: non_core_id,
                 target_type: non_core_node.non_core_type,
+                clique_id: None,
             };
             out.push(row);
         }
         Ok(out)
     }
 
-    /// convenience function, used for debugging and "long-format" printing
+    /// convenience function, used for debugging and "long-format" printing. If a
+    /// `clique_index` is provided, it is printed right after the graph_id. Any
+    /// `extra_columns` are printed at the end of each row.
     pub fn print(
         &self,
         graph_id: GraphId,
+        clique_index: Option<usize>,
+        extra_columns: &[String],
         target_types: &[String],
         core_type: &str,
         output: &mut Output,
     ) -> CLQResult<()> {
+        self.print_with_interner(
+            graph_id,
+            clique_index,
+            extra_columns,
+            target_types,
+            core_type,
+            None,
+            output,
+        )
+    }
+
+    /// same as `print`, but node ids are printed as the strings they were interned from,
+    /// if an interner is provided.
+    #[allow(clippy::too_many_arguments)]
+    pub fn print_with_interner(
+        &self,
+        graph_id: GraphId,
+        clique_index: Option<usize>,
+        extra_columns: &[String],
+        target_types: &[String],
+        core_type: &str,
+        interner: Option<&NodeIdInterner>,
+        output: &mut Output,
+    ) -> CLQResult<()> {
+        let key: String = match clique_index {
+            Some(ix) => format!("{}\t{}", graph_id.value(), ix),
+            None => graph_id.value().to_string(),
+        };
         for output_row in &self.get_output_rows(graph_id)? {
             let node_type: String = match output_row.target_type {
                 // this is hacky -- when t is 0 it's an indication of this being the
@@ -252,12 +702,16 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
                 Some(t) => target_types[t.value() - 1].clone(),
                 None => core_type.to_string(),
             };
-            output.print(format!(
-                "{}\t{}\t{}",
-                graph_id.value(),
-                output_row.node_id.value(),
-                node_type
-            ))?;
+            let node_id: String = match interner {
+                Some(interner) => interner.get_original_id(graph_id, output_row.node_id)?,
+                None => output_row.node_id.value().to_string(),
+            };
+            let mut line: String = format!("{}\t{}\t{}", key, node_id, node_type);
+            for column in extra_columns {
+                line.push('\t');
+                line.push_str(column);
+            }
+            output.print(line)?;
         }
         Ok(())
     }
@@ -273,6 +727,13 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
                 true => self.score,
                 false => None,
             },
+            tie_weights: self.tie_weights.clone(),
+            num_ties: self.num_ties,
+            total_tie_weight: self.total_tie_weight,
+            max_core_edge_count: self.max_core_edge_count,
+            non_core_tie_weight: self.non_core_tie_weight,
+            non_core_type_counts: self.non_core_type_counts.clone(),
+            time_span: self.time_span,
         }
     }
 
@@ -293,13 +754,19 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
 
     /// finds nodes that are already connected to the candidate's members, but not
     /// among the members themselves. Sorts in descending order by the number of
-    /// ties with members, returning at most num_to_search expansion candidates.
+    /// ties with members, returning at most num_to_search expansion candidates
+    /// for each move in `move_set`: candidates with an adjacent node added, the
+    /// candidate with its weakest member removed, and candidates with the weakest
+    /// member swapped for an adjacent node. Nodes whose addition would exceed the
+    /// maxima of `size_constraints` (if any) are skipped.
     fn get_expansion_candidates(
         &self,
         num_to_search: usize,
-        visited_candidates: &mut HashSet<u64>,
+        visited_candidates: &mut VisitedCandidates,
+        move_set: &MoveSet,
+        size_constraints: Option<&SizeConstraints>,
     ) -> CLQResult<Vec<Self>> {
-        assert!(!visited_candidates.contains(&self.checksum.unwrap()));
+        assert!(!visited_candidates.contains(self));
         let mut tie_counts: Vec<(NodeId, usize)> = Vec::new();
         for node_id in self.get_adjacent_nodes()? {
             let node = self.get_node(node_id);
@@ -312,33 +779,77 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
         // sort by number of ties, with node_id as tie breaker for deterministic behaviour
         tie_counts.sort_by_key(|k| (Reverse(k.1), k.0));
 
-        let mut i = 0;
         let mut expansion_candidates: Vec<Self> = Vec::new();
-        for (node_id, _num_ties) in tie_counts {
-            let candidate = self.expand_with_node(node_id)?;
-            assert!(self.checksum != candidate.checksum);
-            if !visited_candidates.contains(&candidate.checksum.unwrap()) {
-                expansion_candidates.push(candidate);
-                i += 1;
+        // a candidate remains open for further expansion until all its add moves
+        // have been tried.
+        let mut is_exhausted: bool = true;
+        if move_set.add {
+            let mut i = 0;
+            for &(node_id, _num_ties) in &tie_counts {
+                if i == num_to_search {
+                    is_exhausted = false;
+                    break;
+                }
+                if let Some(constraints) = size_constraints {
+                    if constraints.is_exceeded_by_adding(self, node_id)? {
+                        continue;
+                    }
+                }
+                let candidate = self.expand_with_node(node_id)?;
+                if !visited_candidates.contains(&candidate) {
+                    expansion_candidates.push(candidate);
+                    i += 1;
+                }
             }
-            if i == num_to_search {
-                return Ok(expansion_candidates);
+        }
+        if move_set.remove || move_set.swap {
+            if let Some(weakest_id) = self.get_weakest_member()? {
+                let mut reduced: Self = self.replicate(false);
+                reduced.remove_node(weakest_id)?;
+                if move_set.swap {
+                    let mut i = 0;
+                    for &(node_id, _num_ties) in &tie_counts {
+                        if i == num_to_search {
+                            break;
+                        }
+                        if let Some(constraints) = size_constraints {
+                            if constraints.is_exceeded_by_adding(&reduced, node_id)? {
+                                continue;
+                            }
+                        }
+                        let candidate = reduced.expand_with_node(node_id)?;
+                        if !visited_candidates.contains(&candidate) {
+                            expansion_candidates.push(candidate);
+                            i += 1;
+                        }
+                    }
+                }
+                if move_set.remove && !visited_candidates.contains(&reduced) {
+                    expansion_candidates.push(reduced);
+                }
             }
         }
-        assert!(self.checksum.unwrap() != 0);
-        visited_candidates.insert(self.checksum.unwrap());
+        if is_exhausted {
+            visited_candidates.insert(self);
+        }
         Ok(expansion_candidates)
     }
 
-    /// finds (up to) num_to_search expansion candidates and scores them.
+    /// finds (up to) num_to_search expansion candidates per move in `move_set`,
+    /// and scores them.
     pub fn one_step_search(
         &self,
         num_to_search: usize,
-        visited_candidates: &mut HashSet<u64>,
-        scorer: &Scorer,
+        visited_candidates: &mut VisitedCandidates,
+        scorer: &dyn Scorer<TGraph>,
+        move_set: &MoveSet,
     ) -> CLQResult<Vec<Self>> {
-        let mut expansion_candidates: Vec<Self> =
-            self.get_expansion_candidates(num_to_search, visited_candidates)?;
+        let mut expansion_candidates: Vec<Self> = self.get_expansion_candidates(
+            num_to_search,
+            visited_candidates,
+            move_set,
+            scorer.get_size_constraints(),
+        )?;
         for candidate in &mut expansion_candidates {
             let score = scorer.score(candidate)?;
             candidate.set_score(score)?;
@@ -348,7 +859,7 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
 
     /// used to find nodes that are "adjacent" -- i.e., connected with existing members,
     /// but not among the members themselves.
-    fn get_adjacent_nodes(&self) -> CLQResult<HashSet<NodeId>> {
+    pub fn get_adjacent_nodes(&self) -> CLQResult<HashSet<NodeId>> {
         let mut node_set: HashSet<NodeId> = HashSet::new();
         for &node_id in &self.core_ids {
             let node = self.get_node(node_id);
@@ -363,7 +874,8 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
             let non_core_node = self.get_node(non_core_id);
             for edge in &non_core_node.neighbors {
                 let node_id = edge.target_id;
-                if !self.core_ids.contains(&node_id) {
+                // non-core nodes may be tied to other non-core members.
+                if !self.core_ids.contains(&node_id) && !self.non_core_ids.contains(&node_id) {
                     node_set.insert(node_id);
                 }
             }
@@ -376,29 +888,77 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
     /// this is the sum of maximum weights for edges that could connect nodes currently
     /// in the candidates.
     pub fn get_size(&self) -> CLQResult<usize> {
-        let mut size: usize = 0;
-        for &non_core_id in &self.non_core_ids {
-            let non_core_node = self.get_node(non_core_id);
-            size += self.core_ids.len()
-                * non_core_node
-                    .max_edge_count_with_core_node()?
-                    .ok_or_else(CLQError::err_none)?;
-        }
-        Ok(size)
+        Ok(self.core_ids.len() * self.max_core_edge_count)
     }
 
-    /// computes "cliqueness", the density of ties between core and non-core nodes.
+    /// computes "cliqueness", the (weighted) density of ties between core and non-core nodes.
     pub fn get_cliqueness(&self) -> CLQResult<f32> {
         let size = self.get_size()?;
-        let ties_between_nodes = self.count_ties_between_nodes()?;
+        let ties_between_nodes = self.sum_tie_weights_between_nodes()?;
         let cliqueness: f32 = if size > 0 {
-            ties_between_nodes as f32 / size as f32
+            ties_between_nodes / size as f32
         } else {
             1.0
         };
         Ok(cliqueness)
     }
 
+    /// computes the (weighted) density of the typed subgraph induced by the candidate:
+    /// ties between core and non-core members, as well as ties between non-core members
+    /// along `non_core_relations` (pairs of non-core types which may be tied, one per
+    /// relation), over the maximum number of such ties. Equal to `get_cliqueness` if there
+    /// are no such relations.
+    pub fn get_typed_cliqueness(
+        &self,
+        non_core_relations: &[(NodeTypeId, NodeTypeId)],
+    ) -> CLQResult<f32> {
+        let mut size: usize = self.get_size()?;
+        for (source_type, target_type) in non_core_relations {
+            let num_sources: usize = *self.non_core_type_counts.get(source_type).unwrap_or(&0);
+            let num_targets: usize = *self.non_core_type_counts.get(target_type).unwrap_or(&0);
+            size += match source_type == target_type {
+                // ties between nodes of the same type are undirected.
+                true => num_sources * num_sources.saturating_sub(1) / 2,
+                false => num_sources * num_targets,
+            };
+        }
+        let tie_weight: f32 = self.total_tie_weight + self.non_core_tie_weight;
+        Ok(match size {
+            0 => 1.0,
+            _ => tie_weight / size as f32,
+        })
+    }
+
+    /// computes the hyperedge-based counterpart of `get_cliqueness`, if the graph is a
+    /// hypergraph: for each core member, the largest (weighted) fraction of non-core members
+    /// covered by a single one of its hyperedges, averaged over core members. This is 1.0
+    /// iff each core member has a hyperedge containing all non-core members, whereas ties
+    /// spread over several hyperedges do not count as co-occurrence. Returns None if the
+    /// graph is not a hypergraph, or if the candidate has no core members.
+    pub fn get_hyperedge_cliqueness(&self) -> CLQResult<Option<f32>> {
+        let mut total_coverage: f32 = 0.0;
+        for &core_id in &self.core_ids {
+            let hyperedges = match self.graph.get_hyperedges(core_id) {
+                Some(hyperedges) => hyperedges,
+                None => return Ok(None),
+            };
+            let mut max_coverage: f32 = 0.0;
+            for hyperedge in hyperedges {
+                let num_covered: usize = hyperedge
+                    .non_core_ids
+                    .iter()
+                    .filter(|id| self.non_core_ids.contains(id))
+                    .count();
+                max_coverage = max_coverage.max(hyperedge.weight * num_covered as f32);
+            }
+            total_coverage += max_coverage / self.non_core_ids.len().max(1) as f32;
+        }
+        if self.core_ids.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(total_coverage / self.core_ids.len() as f32))
+    }
+
     /// checks if Candidate is a true clique, defined as a subgraph where the total number
     /// of ties between nodes is equal to the maximum number of ties between nodes.
     pub fn is_clique(&self) -> CLQResult<bool> {
@@ -407,52 +967,82 @@ impl<'a, TGraph: GraphBase> Candidate<'a, TGraph> {
 
     /// counts the total number of ties between candidate's core nodes and non_cores
     pub fn count_ties_between_nodes(&self) -> CLQResult<usize> {
-        let mut num_ties: usize = 0;
-        for &non_core_id in &self.non_core_ids {
-            num_ties += self.get_node(non_core_id).count_ties_with_ids(&self.core_ids);
-        }
-        Ok(num_ties)
+        Ok(self.num_ties)
+    }
+
+    /// sums up the weights of ties between candidate's core nodes and non_cores. Equal to
+    /// `count_ties_between_nodes` if the graph is unweighted.
+    pub fn sum_tie_weights_between_nodes(&self) -> CLQResult<f32> {
+        Ok(self.total_tie_weight)
     }
 
-    /// gets densities over each non-core type (useful to compute non-core diversity score)
+    /// gets (weighted) densities over each non-core type (useful to compute non-core diversity
+    /// score)
     fn get_non_core_densities(&self, num_non_core_types: usize) -> CLQResult<Vec<f32>> {
         let mut non_core_max_counts: Vec<usize> = vec![0; num_non_core_types + 1];
-        let mut non_core_out_counts: Vec<usize> = vec![0; num_non_core_types + 1];
+        let mut non_core_out_counts: Vec<f32> = vec![0.0; num_non_core_types + 1];
         for &non_core_id in &self.non_core_ids {
             let non_core = self.get_node(non_core_id);
             let non_core_type_id: NodeTypeId = non_core.non_core_type.ok_or_else(CLQError::err_none)?;
-            let num_ties: usize = non_core.count_ties_with_ids(&self.core_ids);
-            let max_density = non_core
-                .max_edge_count_with_core_node()?
-                .ok_or_else(CLQError::err_none)?;
+            let num_ties: f32 = self.get_member_tie_weight(non_core_id)?;
+            let max_density = non_core.max_edge_count_with_core_node()?.unwrap_or(0);
             non_core_max_counts[non_core_type_id.value()] += max_density * self.core_ids.len();
             non_core_out_counts[non_core_type_id.value()] += num_ties;
         }
         let mut non_core_density: Vec<f32> = Vec::new();
         for i in 1..non_core_max_counts.len() {
-            non_core_density.push(non_core_out_counts[i] as f32 / non_core_max_counts[i] as f32);
+            non_core_density.push(non_core_out_counts[i] / non_core_max_counts[i] as f32);
         }
         Ok(non_core_density)
     }
 
-    /// gets core densities for each non-core node
-    fn get_core_densities(&self) -> Vec<f32> {
-        let mut counts: Vec<f32> = Vec::new();
-        let max_size: usize = self
-            .non_core_ids
+    /// gets the (weighted) density of ties of type `edge_type`, between core members and
+    /// non-core members of type `non_core_type`. Returns None if there are no such members.
+    pub fn get_edge_type_density(
+        &self,
+        edge_type: EdgeTypeId,
+        non_core_type: NodeTypeId,
+    ) -> CLQResult<Option<f32>> {
+        let mut num_non_cores: usize = 0;
+        let mut tie_weight: f32 = 0.0;
+        for &non_core_id in &self.non_core_ids {
+            let non_core = self.get_node(non_core_id);
+            if non_core.non_core_type.ok_or_else(CLQError::err_none)? != non_core_type {
+                continue;
+            }
+            num_non_cores += 1;
+            for edge in &non_core.neighbors {
+                if edge.edge_type == edge_type && self.core_ids.contains(&edge.target_id) {
+                    tie_weight += edge.weight;
+                }
+            }
+        }
+        let max_tie_count: usize = num_non_cores * self.core_ids.len();
+        Ok(match max_tie_count {
+            0 => None,
+            n => Some(tie_weight / n as f32),
+        })
+    }
+
+    /// gets (weighted) densities for each relation, i.e. (edge type, non-core type) pair,
+    /// as per `get_edge_type_density`.
+    pub fn get_edge_type_densities(
+        &self,
+        relations: &[(EdgeTypeId, NodeTypeId)],
+    ) -> CLQResult<Vec<Option<f32>>> {
+        relations
             .iter()
-            .map(|&id| {
-                self.get_node(id)
-                    .max_edge_count_with_core_node()
-                    .unwrap()
-                    .unwrap()
-            })
-            .sum();
+            .map(|&(edge_type, non_core_type)| self.get_edge_type_density(edge_type, non_core_type))
+            .collect()
+    }
+
+    /// gets (weighted) core densities for each non-core node
+    fn get_core_densities(&self) -> CLQResult<Vec<f32>> {
+        let mut counts: Vec<f32> = Vec::new();
         for &node_id in &self.core_ids {
-            let node = self.get_node(node_id);
-            let num_ties: usize = node.count_ties_with_ids(&self.non_core_ids);
-            counts.push(num_ties as f32 / max_size as f32);
+            let num_ties: f32 = self.get_member_tie_weight(node_id)?;
+            counts.push(num_ties / self.max_core_edge_count as f32);
         }
-        counts
+        Ok(counts)
     }
 }
@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate serde_json;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+
+use crate::dachshund::candidate::Candidate;
+use crate::dachshund::error::CLQResult;
+use crate::dachshund::graph::GraphBase;
+use crate::dachshund::id_types::{GraphId, NodeId};
+use crate::dachshund::interner::NodeIdInterner;
+use crate::dachshund::row::CliqueRow;
+use crate::dachshund::scorer::Scorer;
+use crate::dachshund::trace::JsonLinesTraceSink;
+
+/// Describes how a prior (seed) clique compares to the top candidate found by the
+/// current search on the same graph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CliqueDrift {
+    pub graph_id: GraphId,
+    /// the prior clique's clique_id, if the seed rows carried one.
+    pub clique_id: Option<usize>,
+    /// nodes in the top candidate which were not in the prior clique, sorted.
+    pub joined: Vec<NodeId>,
+    /// nodes in the prior clique which are not in the top candidate, sorted.
+    pub left: Vec<NodeId>,
+    /// score of the prior clique under the current data (restricted to the nodes still in
+    /// the graph), or None if none of its nodes are in the graph anymore.
+    pub prior_score: Option<f32>,
+    /// size of the intersection over size of the union of both node sets.
+    pub jaccard: f32,
+}
+
+impl CliqueDrift {
+    /// compares each of the prior cliques described by `clique_rows` (grouped by
+    /// clique_id) to `top_candidate`, re-scoring the prior cliques with `scorer`.
+    pub fn from_clique_rows<TGraph: GraphBase>(
+        clique_rows: &[CliqueRow],
+        graph: &TGraph,
+        top_candidate: &Candidate<TGraph>,
+        scorer: &dyn Scorer<TGraph>,
+    ) -> CLQResult<Vec<Self>> {
+        let mut prior_cliques: BTreeMap<Option<usize>, Vec<CliqueRow>> = BTreeMap::new();
+        for row in clique_rows {
+            prior_cliques.entry(row.clique_id).or_default().push(*row);
+        }
+        let current: BTreeSet<NodeId> = top_candidate.get_canonical_ids().into_iter().collect();
+        let mut drifts: Vec<Self> = Vec::new();
+        for (clique_id, rows) in prior_cliques {
+            let graph_id: GraphId = rows[0].graph_id;
+            let prior: BTreeSet<NodeId> = rows.iter().map(|x| x.node_id).collect();
+            let prior_score: Option<f32> =
+                match Candidate::from_clique_rows(rows, graph, scorer)? {
+                    Some(candidate) => Some(candidate.get_score()?),
+                    None => None,
+                };
+            let num_shared: usize = prior.intersection(&current).count();
+            let num_total: usize = prior.union(&current).count();
+            drifts.push(Self {
+                graph_id,
+                clique_id,
+                joined: current.difference(&prior).cloned().collect(),
+                left: prior.difference(&current).cloned().collect(),
+                prior_score,
+                jaccard: match num_total {
+                    0 => 0.0,
+                    n => num_shared as f32 / n as f32,
+                },
+            });
+        }
+        Ok(drifts)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "graph_id": self.graph_id.value(),
+            "clique_id": self.clique_id,
+            "joined": self.joined.iter().map(|x| x.value()).collect::<Vec<i64>>(),
+            "left": self.left.iter().map(|x| x.value()).collect::<Vec<i64>>(),
+            "prior_score": self.prior_score,
+            "jaccard": self.jaccard,
+        })
+    }
+
+    /// as `to_json`, but with node ids mapped back to the strings they were interned from,
+    /// if an interner is provided (see `Candidate::to_json_with_interner`).
+    pub fn to_json_with_interner(&self, interner: Option<&NodeIdInterner>) -> CLQResult<Value> {
+        let mut value: Value = self.to_json();
+        if let Some(interner) = interner {
+            let original_ids = |ids: &[NodeId]| -> CLQResult<Vec<String>> {
+                ids.iter()
+                    .map(|&id| interner.get_original_id(self.graph_id, id))
+                    .collect()
+            };
+            value["joined"] = json!(original_ids(&self.joined)?);
+            value["left"] = json!(original_ids(&self.left)?);
+        }
+        Ok(value)
+    }
+}
+
+/// Receives the drift of each prior clique, along with the interner its node ids come
+/// from (if any). Sinks may be shared by searches running on multiple threads.
+pub trait DriftSink: Send + Sync {
+    fn record(&self, drift: &CliqueDrift, interner: Option<&NodeIdInterner>) -> CLQResult<()>;
+}
+
+/// Lets a sink be shared by several transformers (see `MultiCoreTransformer`).
+impl<T: DriftSink + ?Sized> DriftSink for Arc<T> {
+    fn record(&self, drift: &CliqueDrift, interner: Option<&NodeIdInterner>) -> CLQResult<()> {
+        (**self).record(drift, interner)
+    }
+}
+
+impl<W: Write + Send> DriftSink for JsonLinesTraceSink<W> {
+    fn record(&self, drift: &CliqueDrift, interner: Option<&NodeIdInterner>) -> CLQResult<()> {
+        self.write_line(&drift.to_json_with_interner(interner)?)
+    }
+}
@@ -0,0 +1,223 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+use std::collections::{BTreeSet, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::dachshund::beam::BeamSearchResult;
+use crate::dachshund::candidate::Candidate;
+use crate::dachshund::error::{CLQError, CLQResult};
+use crate::dachshund::graph::GraphBase;
+use crate::dachshund::id_types::NodeId;
+use crate::dachshund::scorer::Scorer;
+
+/// Keeps track of the best candidate found so far, and of the resources used, over the
+/// course of an exact search.
+struct ExactSearchState<'a, TGraph>
+where
+    TGraph: GraphBase,
+{
+    best_candidate: Option<Candidate<'a, TGraph>>,
+    best_score: f32,
+    num_steps: usize,
+    start: Instant,
+    out_of_budget: bool,
+}
+
+/// Exact (quasi-)biclique solver, meant for small graphs, or to benchmark the beam search.
+/// Enumerates every connected candidate in the graph via branch-and-bound: a candidate is
+/// expanded one adjacent node at a time, and a branch is abandoned as soon as the `Scorer`'s
+/// upper bound guarantees it cannot beat the best candidate found so far. Since exhaustive
+/// enumeration is exponential in the worst case, the search is limited by a node budget (the
+/// maximum number of candidates scored) and a timeout. If either runs out, the best candidate
+/// found so far is returned, and `exhausted_search` will return false.
+pub struct ExactSolver<'a, TGraph>
+where
+    TGraph: GraphBase,
+{
+    graph: &'a TGraph,
+    scorer: Box<dyn Scorer<TGraph> + 'a>,
+    node_budget: usize,
+    timeout: Duration,
+    exhausted: bool,
+}
+
+impl<'a, TGraph: GraphBase> ExactSolver<'a, TGraph> {
+    /// creates a new solver. Parameters:
+    ///     - `graph`: a reference to a `TGraph` object (typically constructed by a transformer).
+    ///     - `scorer`: the `Scorer` used to compute the objective maximized by the search. Its
+    ///     upper bound (if any) is used to prune the search.
+    ///     - `node_budget`: maximum number of candidates to score.
+    ///     - `timeout`: maximum amount of time to spend searching.
+    pub fn new(
+        graph: &'a TGraph,
+        scorer: Box<dyn Scorer<TGraph> + 'a>,
+        node_budget: usize,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            graph,
+            scorer,
+            node_budget,
+            timeout,
+            exhausted: false,
+        }
+    }
+
+    /// whether the last search explored the full search space (i.e., whether its result
+    /// is guaranteed to be optimal).
+    pub fn exhausted_search(&self) -> bool {
+        self.exhausted
+    }
+
+    /// scores a candidate, updating the search state if it is the best one found so far.
+    fn visit(
+        &self,
+        state: &mut ExactSearchState<'a, TGraph>,
+        candidate: &mut Candidate<'a, TGraph>,
+    ) -> CLQResult<()> {
+        let score = self.scorer.score(candidate)?;
+        candidate.set_score(score)?;
+        state.num_steps += 1;
+        if state.best_candidate.is_none() || score > state.best_score {
+            state.best_candidate = Some(candidate.replicate(true));
+            state.best_score = score;
+        }
+        Ok(())
+    }
+
+    fn is_out_of_budget(&self, state: &mut ExactSearchState<'a, TGraph>) -> bool {
+        if state.num_steps >= self.node_budget || state.start.elapsed() >= self.timeout {
+            state.out_of_budget = true;
+        }
+        state.out_of_budget
+    }
+
+    /// enumerates all connected candidates that contain `candidate`, contain no `excluded`
+    /// node, and can be reached by adding nodes from `frontier` (and their own neighbors).
+    fn branch(
+        &self,
+        state: &mut ExactSearchState<'a, TGraph>,
+        candidate: &Candidate<'a, TGraph>,
+        frontier: &BTreeSet<NodeId>,
+        excluded: &mut HashSet<NodeId>,
+    ) -> CLQResult<()> {
+        if frontier.is_empty() {
+            return Ok(());
+        }
+        let num_excluded_core_ids: usize = excluded
+            .iter()
+            .filter(|&&id| self.graph.get_node(id).is_core())
+            .count();
+        let num_core_ids: usize =
+            self.graph.get_core_ids().len() - candidate.core_ids.len() - num_excluded_core_ids;
+        let num_non_core_ids: usize = self
+            .graph
+            .get_non_core_ids()
+            .ok_or_else(CLQError::err_none)?
+            .len()
+            - candidate.non_core_ids.len()
+            - (excluded.len() - num_excluded_core_ids);
+        if let Some(bound) = self
+            .scorer
+            .get_upper_bound(candidate, num_core_ids, num_non_core_ids)?
+        {
+            if bound <= state.best_score {
+                return Ok(());
+            }
+        }
+        let mut newly_excluded: Vec<NodeId> = Vec::new();
+        for &node_id in frontier {
+            if self.is_out_of_budget(state) {
+                break;
+            }
+            let mut expanded: Candidate<TGraph> = candidate.replicate(false);
+            expanded.add_node(node_id)?;
+            self.visit(state, &mut expanded)?;
+            // the remaining frontier, plus whatever becomes reachable through node_id.
+            let mut new_frontier: BTreeSet<NodeId> = frontier
+                .iter()
+                .filter(|&&id| id != node_id && !excluded.contains(&id))
+                .cloned()
+                .collect();
+            for edge in &self.graph.get_node(node_id).neighbors {
+                let neighbor_id = edge.target_id;
+                if !excluded.contains(&neighbor_id)
+                    && !expanded.core_ids.contains(&neighbor_id)
+                    && !expanded.non_core_ids.contains(&neighbor_id)
+                {
+                    new_frontier.insert(neighbor_id);
+                }
+            }
+            self.branch(state, &expanded, &new_frontier, excluded)?;
+            // all candidates containing node_id have now been enumerated.
+            excluded.insert(node_id);
+            newly_excluded.push(node_id);
+        }
+        for node_id in newly_excluded {
+            excluded.remove(&node_id);
+        }
+        Ok(())
+    }
+
+    /// runs the search, returning the best candidate found. `num_steps` is the number of
+    /// candidates scored.
+    pub fn run_search(&mut self) -> CLQResult<BeamSearchResult<'a, TGraph>> {
+        let mut state: ExactSearchState<TGraph> = ExactSearchState {
+            best_candidate: None,
+            best_score: 0.0,
+            num_steps: 0,
+            start: Instant::now(),
+            out_of_budget: false,
+        };
+        let root_ids: Vec<NodeId> = self
+            .graph
+            .get_core_ids()
+            .iter()
+            .chain(
+                self.graph
+                    .get_non_core_ids()
+                    .ok_or_else(CLQError::err_none)?
+                    .iter(),
+            )
+            .cloned()
+            .collect();
+        // candidates containing a root are only enumerated from that root, so earlier
+        // roots are excluded from subsequent searches.
+        let mut excluded: HashSet<NodeId> = HashSet::new();
+        for root_id in root_ids {
+            if self.is_out_of_budget(&mut state) {
+                break;
+            }
+            let mut candidate: Candidate<TGraph> = Candidate::init_blank(self.graph);
+            candidate.add_node(root_id)?;
+            self.visit(&mut state, &mut candidate)?;
+            let frontier: BTreeSet<NodeId> = self
+                .graph
+                .get_node(root_id)
+                .neighbors
+                .iter()
+                .map(|edge| edge.target_id)
+                .filter(|id| !excluded.contains(id))
+                .collect();
+            self.branch(&mut state, &candidate, &frontier, &mut excluded)?;
+            excluded.insert(root_id);
+        }
+        self.exhausted = !state.out_of_budget;
+        let top_candidate = state
+            .best_candidate
+            .ok_or_else(|| CLQError::from("Exact search found no candidates."))?;
+        Ok(BeamSearchResult {
+            top_candidate,
+            num_steps: state.num_steps,
+            truncated: state.out_of_budget,
+            stop_reason: match state.out_of_budget {
+                true => Some("budget_exceeded"),
+                false => None,
+            },
+        })
+    }
+}
@@ -6,6 +6,7 @@
  */
 extern crate nalgebra as na;
 use crate::dachshund::error::{CLQError, CLQResult};
+use crate::dachshund::hypergraph::Hyperedge;
 use crate::dachshund::id_types::{GraphId, EdgeTypeId, NodeId, NodeTypeId};
 use crate::dachshund::node::{Node, NodeEdge};
 use crate::dachshund::row::EdgeRow;
@@ -26,15 +27,23 @@ where
     fn has_node(&self, node_id: NodeId) -> bool;
     fn get_node(&self, node_id: NodeId) -> &Node;
     fn count_edges(&self) -> usize;
+    fn count_nodes(&self) -> usize;
+    /// returns the hyperedges containing core node `core_id` if the graph is a hypergraph
+    /// (see `HyperGraph`), and None otherwise.
+    fn get_hyperedges(&self, _core_id: NodeId) -> Option<&[Hyperedge]> {
+        None
+    }
 }
 type GraphMatrix = DMatrix<f64>;
 type OrderedNodeSet = BTreeSet<NodeId>;
 type OrderedEdgeSet = BTreeSet<(NodeId, NodeId)>;
 type NodePredecessors = HashMap<NodeId, Vec<NodeId>>;
 
-/// Keeps track of a bipartite graph composed of "core" and "non-core" nodes. Only core ->
-/// non-core connections may exist in the graph. The neighbors of core nodes are non-cores, the
-/// neighbors of non-core nodes are cores. Graph edges are stored in the neighbors field of
+/// Keeps track of a typed graph composed of "core" and "non-core" nodes. Core -> non-core
+/// connections may exist in the graph, as well as connections between non-core nodes (if
+/// the typespec has relations between non-core types), but not between core nodes. The
+/// neighbors of core nodes are non-cores, the neighbors of non-core nodes are cores or
+/// other non-cores. Graph edges are stored in the neighbors field of
 /// each node. If the id of a node is known, its Node object can be retrieved via the
 /// nodes HashMap. To iterate over core and non-core nodes, the struct also provides the
 /// core_ids and non_core_ids vectors.
@@ -66,6 +75,9 @@ impl GraphBase for Graph {
         }
         num_edges
     }
+    fn count_nodes(&self) -> usize {
+        self.nodes.len()
+    }
 }
 /// Keeps track of a simple undirected graph, composed of nodes without any type information.
 pub struct SimpleUndirectedGraph {
@@ -97,6 +109,9 @@ impl GraphBase for SimpleUndirectedGraph {
         }
         num_edges
     }
+    fn count_nodes(&self) -> usize {
+        self.nodes.len()
+    }
 }
 
 /// Trait encapsulting the logic required to build a graph from a set of edge
@@ -149,14 +164,20 @@ where
                 .get_mut(&r.source_id)
                 .ok_or_else(CLQError::err_none)?
                 .neighbors
-                .push(NodeEdge::new(r.edge_type_id, r.target_id));
+                .push(
+                    NodeEdge::with_weight(r.edge_type_id, r.target_id, r.weight)
+                        .with_timestamp(r.timestamp),
+                );
             // edges with the same source and target type should not be repeated
             if r.source_type_id != r.target_type_id {
                 node_map
                     .get_mut(&r.target_id)
                     .ok_or_else(CLQError::err_none)?
                     .neighbors
-                    .push(NodeEdge::new(r.edge_type_id, r.source_id));
+                    .push(
+                        NodeEdge::with_weight(r.edge_type_id, r.source_id, r.weight)
+                            .with_timestamp(r.timestamp),
+                    );
             }
         }
         Ok(())
@@ -195,7 +216,8 @@ where
     }
     /// creates a TGraph object from a vector of rows. Client must provide
     /// graph_id which must match with each row's graph_id. If min_degree
-    /// is provided, the graph is additionally pruned.
+    /// is provided, the graph is additionally pruned. Sources of rows whose source type
+    /// is not the core type are non-core nodes.
     fn new(
         graph_id: GraphId,
         rows: &[EdgeRow],
@@ -206,7 +228,12 @@ where
         let mut target_type_ids: HashMap<NodeId, NodeTypeId> = HashMap::new();
         for r in rows.iter() {
             assert!(graph_id == r.graph_id);
-            source_ids.insert(r.source_id);
+            if r.source_type_id.is_core() {
+                source_ids.insert(r.source_id);
+            } else {
+                target_ids.insert(r.source_id);
+                target_type_ids.insert(r.source_id, r.source_type_id);
+            }
             target_ids.insert(r.target_id);
             target_type_ids.insert(r.target_id, r.target_type_id);
         }
@@ -233,6 +260,9 @@ where
     fn prune(graph: TGraph, rows: &[EdgeRow], min_degree: usize) -> CLQResult<TGraph> {
         let mut target_type_ids: HashMap<NodeId, NodeTypeId> = HashMap::new();
         for r in rows.iter() {
+            if !r.source_type_id.is_core() {
+                target_type_ids.insert(r.source_id, r.source_type_id);
+            }
             target_type_ids.insert(r.target_id, r.target_type_id);
         }
         let (filtered_source_ids, filtered_target_ids, filtered_rows) =
@@ -0,0 +1,157 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::dachshund::error::{CLQError, CLQResult};
+use crate::dachshund::graph::GraphBase;
+use crate::dachshund::id_types::{EdgeTypeId, GraphId, NodeId, NodeTypeId};
+use crate::dachshund::node::{Node, NodeEdge};
+use crate::dachshund::row::HyperedgeRow;
+
+/// A (possibly weighted) hyperedge, e.g. an event, tying a core node to several non-core
+/// nodes at once. The core node is implied by where the hyperedge is stored (see
+/// `HyperGraph`). `non_core_ids` is sorted and free of duplicates.
+pub struct Hyperedge {
+    pub edge_type: EdgeTypeId,
+    pub non_core_ids: Vec<NodeId>,
+    pub weight: f32,
+}
+
+/// Keeps track of a typed hypergraph, in which each hyperedge ties a core node to a set of
+/// non-core nodes. So that searches can run on it as they would on a `Graph`, the neighbors
+/// of each node are given by the pairwise projection of the hyperedges: a core node is tied
+/// to each non-core node it shares a hyperedge with (once per edge type, with the largest
+/// weight among these hyperedges). The hyperedges themselves are kept in the hyperedges
+/// HashMap, keyed by core node, and are used to compute cliqueness (see
+/// `Candidate::get_hyperedge_cliqueness`), so that non-core nodes only count as co-occurring
+/// if they belong to the same hyperedge.
+pub struct HyperGraph {
+    pub nodes: HashMap<NodeId, Node>,
+    pub core_ids: Vec<NodeId>,
+    pub non_core_ids: Vec<NodeId>,
+    pub hyperedges: HashMap<NodeId, Vec<Hyperedge>>,
+}
+impl GraphBase for HyperGraph {
+    fn get_core_ids(&self) -> &Vec<NodeId> {
+        &self.core_ids
+    }
+    fn get_non_core_ids(&self) -> Option<&Vec<NodeId>> {
+        Some(&self.non_core_ids)
+    }
+    fn get_mut_nodes(&mut self) -> &mut HashMap<NodeId, Node> {
+        &mut self.nodes
+    }
+    fn has_node(&self, node_id: NodeId) -> bool {
+        self.nodes.contains_key(&node_id)
+    }
+    fn get_node(&self, node_id: NodeId) -> &Node {
+        &self.nodes[&node_id]
+    }
+    /// counts the edges of the pairwise projection.
+    fn count_edges(&self) -> usize {
+        let mut num_edges: usize = 0;
+        for node in self.nodes.values() {
+            num_edges += node.neighbors.len();
+        }
+        num_edges
+    }
+    fn count_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+    fn get_hyperedges(&self, core_id: NodeId) -> Option<&[Hyperedge]> {
+        Some(self.hyperedges.get(&core_id).map_or(&[], |x| x.as_slice()))
+    }
+}
+impl HyperGraph {
+    pub fn count_hyperedges(&self) -> usize {
+        self.hyperedges.values().map(|x| x.len()).sum()
+    }
+}
+
+/// Builds a `HyperGraph` from hyperedge rows.
+pub struct HyperGraphBuilder {}
+impl HyperGraphBuilder {
+    /// creates a HyperGraph from a vector of rows. Client must provide graph_id which must
+    /// match with each row's graph_id. Fails if a node is used both as a core and as a
+    /// non-core node, or with several non-core types.
+    pub fn from_rows(graph_id: GraphId, rows: &[HyperedgeRow]) -> CLQResult<HyperGraph> {
+        let mut core_ids: BTreeSet<NodeId> = BTreeSet::new();
+        let mut non_core_type_ids: BTreeMap<NodeId, NodeTypeId> = BTreeMap::new();
+        // ties of the pairwise projection, keyed by (core_id, edge type, non_core_id), with
+        // the largest weight among the hyperedges they stem from.
+        let mut ties: BTreeMap<(NodeId, usize, NodeId), f32> = BTreeMap::new();
+        let mut hyperedges: HashMap<NodeId, Vec<Hyperedge>> = HashMap::new();
+        for r in rows.iter() {
+            assert!(graph_id == r.graph_id);
+            if r.non_core_ids.len() != r.non_core_type_ids.len() {
+                return Err(CLQError::from(format!(
+                    "Hyperedge of core node {} has {} non-core nodes, but {} types.",
+                    r.core_id,
+                    r.non_core_ids.len(),
+                    r.non_core_type_ids.len()
+                )));
+            }
+            core_ids.insert(r.core_id);
+            for (&non_core_id, &type_id) in r.non_core_ids.iter().zip(&r.non_core_type_ids) {
+                if let Some(&existing_type_id) = non_core_type_ids.get(&non_core_id) {
+                    if existing_type_id != type_id {
+                        return Err(CLQError::from(format!(
+                            "Node {} is used with several non-core types.",
+                            non_core_id
+                        )));
+                    }
+                }
+                non_core_type_ids.insert(non_core_id, type_id);
+                let tie = ties
+                    .entry((r.core_id, r.edge_type_id.value(), non_core_id))
+                    .or_insert(0.0);
+                *tie = tie.max(r.weight);
+            }
+            let mut non_core_ids: Vec<NodeId> = r.non_core_ids.clone();
+            non_core_ids.sort();
+            non_core_ids.dedup();
+            hyperedges.entry(r.core_id).or_default().push(Hyperedge {
+                edge_type: r.edge_type_id,
+                non_core_ids,
+                weight: r.weight,
+            });
+        }
+        if let Some(id) = core_ids.iter().find(|id| non_core_type_ids.contains_key(id)) {
+            return Err(CLQError::from(format!(
+                "Node {} is used both as a core and as a non-core node.",
+                id
+            )));
+        }
+
+        let mut nodes: HashMap<NodeId, Node> = HashMap::new();
+        for &id in &core_ids {
+            nodes.insert(id, Node::new(id, true, None, Vec::new()));
+        }
+        for (&id, &type_id) in &non_core_type_ids {
+            nodes.insert(id, Node::new(id, false, Some(type_id), Vec::new()));
+        }
+        for (&(core_id, edge_type, non_core_id), &weight) in &ties {
+            let edge_type: EdgeTypeId = edge_type.into();
+            nodes
+                .get_mut(&core_id)
+                .ok_or_else(CLQError::err_none)?
+                .neighbors
+                .push(NodeEdge::with_weight(edge_type, non_core_id, weight));
+            nodes
+                .get_mut(&non_core_id)
+                .ok_or_else(CLQError::err_none)?
+                .neighbors
+                .push(NodeEdge::with_weight(edge_type, core_id, weight));
+        }
+        Ok(HyperGraph {
+            nodes,
+            core_ids: core_ids.into_iter().collect(),
+            non_core_ids: non_core_type_ids.keys().cloned().collect(),
+            hyperedges,
+        })
+    }
+}
@@ -46,7 +46,8 @@ where
     }
 }
 
-/// An opaque identifier for edge types. Not interpreted by dachshund logic in any way.
+/// An opaque identifier for edge types, only used to tell apart ties of different types
+/// (e.g. in edge type constraints).
 #[derive(Hash, Copy, Clone, Debug, PartialEq, Eq)]
 pub struct EdgeTypeId {
     id: usize,
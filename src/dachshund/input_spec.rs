@@ -0,0 +1,295 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate serde_json;
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::dachshund::error::{CLQError, CLQResult};
+
+/// Describes the layout of delimited input: the delimiter, whether the first line is a
+/// header row, and where to find each of the columns a transformer expects. Columns are
+/// looked up in the header row under their expected name (e.g. "core_id"), unless
+/// `column_mapping` maps the expected name to another one (e.g. "user_email"). Without a
+/// header row, columns are expected in their usual positions, unless `column_mapping`
+/// maps them to (0-based) column indices. Input columns that are not expected are ignored.
+/// Fields cannot contain the delimiter (there is no quoting), nor tabs.
+///
+/// Alternatively, input lines can be JSON objects (JSON Lines), in which case each column is
+/// read from the key of the same name, unless `column_mapping` maps it to another key.
+/// Missing keys and nulls are read as empty fields, and lists (e.g. the non-core ids of a
+/// hyperedge) as comma-separated fields.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputSpec {
+    pub delimiter: char,
+    pub has_header: bool,
+    pub column_mapping: BTreeMap<String, String>,
+    pub json_lines: bool,
+}
+impl Default for InputSpec {
+    /// tab-separated input, without a header row, with columns in their usual positions.
+    fn default() -> Self {
+        Self {
+            delimiter: '\t',
+            has_header: false,
+            column_mapping: BTreeMap::new(),
+            json_lines: false,
+        }
+    }
+}
+impl InputSpec {
+    /// parses an input spec of the form:
+    /// {"delimiter": ",", "header": true, "columns": {"core_id": "user_email"}}
+    /// or, for JSON Lines input, of the form:
+    /// {"format": "json_lines", "columns": {"core_id": "user_email"}}
+    /// All fields are optional, and default as per `InputSpec::default`.
+    pub fn from_json(json: &str) -> CLQResult<Self> {
+        let value: Value = serde_json::from_str(json)?;
+        let fields = value
+            .as_object()
+            .ok_or_else(|| CLQError::from("Input spec must be a JSON object."))?;
+        let mut spec = InputSpec::default();
+        for (key, field) in fields {
+            match key.as_str() {
+                "delimiter" => {
+                    let delimiter: &str = field.as_str().unwrap_or_default();
+                    let mut chars = delimiter.chars();
+                    spec.delimiter = match (chars.next(), chars.next()) {
+                        (Some(c), None) => c,
+                        _ => {
+                            return Err(CLQError::from(format!(
+                                "Delimiter must be a single character, got: {}",
+                                field
+                            )))
+                        }
+                    };
+                }
+                "format" => {
+                    spec.json_lines = match field.as_str() {
+                        Some("delimited") => false,
+                        Some("json_lines") => true,
+                        _ => {
+                            return Err(CLQError::from(format!(
+                                "Unknown input format: {} (expected delimited or json_lines).",
+                                field
+                            )))
+                        }
+                    };
+                }
+                "header" => {
+                    spec.has_header = field
+                        .as_bool()
+                        .ok_or_else(|| CLQError::from("Input spec header must be a boolean."))?;
+                }
+                "columns" => {
+                    let columns = field.as_object().ok_or_else(|| {
+                        CLQError::from("Input spec columns must be a JSON object.")
+                    })?;
+                    for (expected_name, name) in columns {
+                        let name: String = match name {
+                            Value::String(s) => s.clone(),
+                            Value::Number(n) => n.to_string(),
+                            _ => {
+                                return Err(CLQError::from(format!(
+                                    "Invalid input column for {}: {}",
+                                    expected_name, name
+                                )))
+                            }
+                        };
+                        spec.column_mapping.insert(expected_name.clone(), name);
+                    }
+                }
+                _ => return Err(CLQError::from(format!("Unknown input spec field: {}", key))),
+            }
+        }
+        Ok(spec)
+    }
+
+    /// whether lines can be passed to transformers as they are.
+    pub fn is_default(&self) -> bool {
+        *self == InputSpec::default()
+    }
+
+    /// creates a `LineNormalizer`, for transformers expecting the given `columns`, of which
+    /// the first `num_required` must be present in the input.
+    pub fn normalizer(
+        &self,
+        columns: &'static [&'static str],
+        num_required: usize,
+    ) -> CLQResult<LineNormalizer> {
+        if let Some(name) = self
+            .column_mapping
+            .keys()
+            .find(|name| !columns.contains(&name.as_str()))
+        {
+            return Err(CLQError::from(format!(
+                "Unknown column: {} (expected one of: {}).",
+                name,
+                columns.join(", ")
+            )));
+        }
+        Ok(LineNormalizer {
+            spec: self.clone(),
+            columns,
+            num_required,
+            aliases: &[],
+            indices: None,
+        })
+    }
+
+    /// finds, for each of `columns`, the index of the input column to read it from (if any).
+    fn get_indices(
+        &self,
+        columns: &[&str],
+        num_required: usize,
+        header: Option<&str>,
+    ) -> CLQResult<Vec<Option<usize>>> {
+        let header_names: Option<Vec<&str>> =
+            header.map(|x| x.split(self.delimiter).map(|name| name.trim()).collect());
+        let mut indices: Vec<Option<usize>> = Vec::new();
+        for (position, &column) in columns.iter().enumerate() {
+            let name: Option<&String> = self.column_mapping.get(column);
+            let index: Option<usize> = match &header_names {
+                Some(names) => {
+                    let name: &str = name.map_or(column, |x| x.as_str());
+                    names.iter().position(|x| *x == name)
+                }
+                None => match name {
+                    Some(n) => Some(n.parse::<usize>().map_err(|_| {
+                        CLQError::from(format!(
+                            "Column {} must be mapped to an index, without a header row.",
+                            column
+                        ))
+                    })?),
+                    None => Some(position),
+                },
+            };
+            if index.is_none() && position < num_required {
+                return Err(CLQError::from(format!("Missing input column: {}", column)));
+            }
+            indices.push(index);
+        }
+        Ok(indices)
+    }
+}
+
+/// Rewrites lines of input described by an `InputSpec` into the tab-separated lines
+/// expected by a transformer, with its columns in their usual positions (and optional
+/// columns left empty if missing).
+pub struct LineNormalizer {
+    spec: InputSpec,
+    columns: &'static [&'static str],
+    num_required: usize,
+    aliases: &'static [(&'static str, &'static str)],
+    // resolved from the header row, if any, when reading the first line.
+    indices: Option<Vec<Option<usize>>>,
+}
+impl LineNormalizer {
+    /// sets (column, key) pairs, such that JSON objects missing the (unmapped) key of a
+    /// column can provide it under another key, e.g. to tell apart the fields of other row
+    /// types sharing the column.
+    pub fn with_aliases(mut self, aliases: &'static [(&'static str, &'static str)]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// returns the normalized line, or None if this is the header row.
+    pub fn normalize(&mut self, line: String) -> CLQResult<Option<String>> {
+        if self.spec.is_default() {
+            return Ok(Some(line));
+        }
+        if self.spec.json_lines {
+            return self.normalize_json(&line).map(Some);
+        }
+        let indices: &Vec<Option<usize>> = match &self.indices {
+            Some(indices) => indices,
+            None => {
+                let header: Option<&str> = match self.spec.has_header {
+                    true => Some(&line),
+                    false => None,
+                };
+                let indices = self
+                    .spec
+                    .get_indices(self.columns, self.num_required, header)?;
+                let indices = self.indices.get_or_insert(indices);
+                if header.is_some() {
+                    return Ok(None);
+                }
+                indices
+            }
+        };
+        let fields: Vec<&str> = line.split(self.spec.delimiter).collect();
+        let mut normalized: Vec<&str> = Vec::new();
+        for (position, index) in indices.iter().enumerate() {
+            let field: Option<&str> = index.and_then(|ix| fields.get(ix).copied());
+            match field {
+                Some(f) if f.contains('\t') => {
+                    return Err(CLQError::from(format!(
+                        "Input fields cannot contain tabs: {}",
+                        line
+                    )))
+                }
+                Some(f) => normalized.push(f),
+                None if position >= self.num_required => normalized.push(""),
+                None => {
+                    return Err(CLQError::from(format!(
+                        "Missing {} column in line: {}",
+                        self.columns[position], line
+                    )))
+                }
+            }
+        }
+        Ok(Some(normalized.join("\t")))
+    }
+
+    /// reads the columns of a JSON object, as described in `InputSpec`.
+    fn normalize_json(&self, line: &str) -> CLQResult<String> {
+        let value: Value = serde_json::from_str(line)?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| CLQError::from(format!("Input line must be a JSON object: {}", line)))?;
+        let mut normalized: Vec<String> = Vec::new();
+        for &column in self.columns {
+            let field: Option<&Value> = match self.spec.column_mapping.get(column) {
+                Some(key) => object.get(key),
+                None => object.get(column).or_else(|| {
+                    self.aliases
+                        .iter()
+                        .filter(|(aliased_column, _)| *aliased_column == column)
+                        .find_map(|(_, key)| object.get(*key))
+                }),
+            };
+            let field: String = match field {
+                Some(Value::Array(values)) => values
+                    .iter()
+                    .map(LineNormalizer::json_to_field)
+                    .collect::<CLQResult<Vec<String>>>()?
+                    .join(","),
+                Some(v) => LineNormalizer::json_to_field(v)?,
+                None => String::new(),
+            };
+            if field.contains('\t') {
+                return Err(CLQError::from(format!(
+                    "Input fields cannot contain tabs: {}",
+                    line
+                )));
+            }
+            normalized.push(field);
+        }
+        Ok(normalized.join("\t"))
+    }
+
+    fn json_to_field(value: &Value) -> CLQResult<String> {
+        match value {
+            Value::Null => Ok(String::new()),
+            Value::String(s) => Ok(s.clone()),
+            Value::Number(_) | Value::Bool(_) => Ok(value.to_string()),
+            _ => Err(CLQError::from(format!("Invalid input field: {}", value))),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use crate::dachshund::error::{CLQError, CLQResult};
+use crate::dachshund::id_types::{GraphId, NodeId};
+
+/// Whether interned node ids are shared by all graphs, or specific to each graph.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InternerScope {
+    Global,
+    PerGraph,
+}
+impl FromStr for InternerScope {
+    type Err = CLQError;
+    fn from_str(s: &str) -> CLQResult<Self> {
+        match s {
+            "global" => Ok(InternerScope::Global),
+            "per_graph" => Ok(InternerScope::PerGraph),
+            _ => Err(CLQError::from(format!("Unknown interner scope: {}", s))),
+        }
+    }
+}
+
+#[derive(Default)]
+struct InternTable {
+    ids: HashMap<String, i64>,
+    reverse_ids: Vec<String>,
+}
+
+/// Maps string node ids (e.g. emails, IPs or device ids) to `NodeId`s and back, so that
+/// inputs do not need to be hashed to integers upstream. As with the graph keys of
+/// `LineProcessor`, ids are numbered in order of appearance. With `InternerScope::PerGraph`,
+/// numbering restarts with each graph, and the ids of a graph can be forgotten once its
+/// output has been printed (see `release`).
+pub struct NodeIdInterner {
+    scope: InternerScope,
+    // keyed by graph_id, or by None if the scope is global.
+    tables: RwLock<HashMap<Option<GraphId>, InternTable>>,
+}
+impl NodeIdInterner {
+    pub fn new(scope: InternerScope) -> Self {
+        Self {
+            scope,
+            tables: RwLock::new(HashMap::new()),
+        }
+    }
+    fn get_table_key(&self, graph_id: GraphId) -> Option<GraphId> {
+        match self.scope {
+            InternerScope::Global => None,
+            InternerScope::PerGraph => Some(graph_id),
+        }
+    }
+    /// returns the NodeId of `key` in the graph, recording it if it is new.
+    pub fn intern(&self, graph_id: GraphId, key: &str) -> NodeId {
+        let mut tables = self.tables.write().unwrap();
+        let table = tables.entry(self.get_table_key(graph_id)).or_default();
+        if let Some(&id) = table.ids.get(key) {
+            return NodeId::from(id);
+        }
+        let id = table.reverse_ids.len() as i64;
+        table.ids.insert(key.to_string(), id);
+        table.reverse_ids.push(key.to_string());
+        NodeId::from(id)
+    }
+    /// returns the string `node_id` was interned from, in the graph.
+    pub fn get_original_id(&self, graph_id: GraphId, node_id: NodeId) -> CLQResult<String> {
+        let tables = self.tables.read().unwrap();
+        tables
+            .get(&self.get_table_key(graph_id))
+            .and_then(|table| table.reverse_ids.get(node_id.value() as usize))
+            .cloned()
+            .ok_or_else(|| {
+                CLQError::from(format!(
+                    "Node {} was not interned in graph {}.",
+                    node_id, graph_id
+                ))
+            })
+    }
+    /// forgets the ids of a graph, if these are specific to it.
+    pub fn release(&self, graph_id: GraphId) {
+        if self.scope == InternerScope::PerGraph {
+            self.tables.write().unwrap().remove(&Some(graph_id));
+        }
+    }
+}
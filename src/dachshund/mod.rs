@@ -4,16 +4,25 @@
  * This source code is licensed under the MIT license found in the
  * LICENSE file in the root directory of this source tree.
  */
+pub mod annealing;
 pub mod beam;
 pub mod candidate;
+pub mod drift;
 pub mod error;
+pub mod exact;
 pub mod graph;
+pub mod hypergraph;
 pub mod id_types;
 pub mod input;
+pub mod input_spec;
+pub mod interner;
+pub mod multi_core_transformer;
 pub mod node;
 pub mod output;
 pub mod row;
 pub mod scorer;
+pub mod significance;
 pub mod simple_transformer;
 pub mod test_utils;
+pub mod trace;
 pub mod transformer;
@@ -0,0 +1,214 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate clap;
+extern crate serde_json;
+
+use std::sync::Arc;
+
+use clap::ArgMatches;
+
+use crate::dachshund::error::{CLQError, CLQResult};
+use crate::dachshund::graph::{GraphBase, GraphBuilder};
+use crate::dachshund::id_types::{GraphId, NodeTypeId};
+use crate::dachshund::input::Input;
+use crate::dachshund::output::Output;
+use crate::dachshund::row::{CliqueRow, EdgeRow, HyperedgeRow, Row};
+use crate::dachshund::trace::JsonLinesTraceSink;
+use crate::dachshund::transformer::{get_required_arg, Transformer};
+
+/// Mines (quasi-)cliques for several core types over the same input, e.g. user -> device
+/// and merchant -> device cliques. Keeps one `Transformer` per core type, set up with the
+/// typespec relations from that core type (and the relations between non-core types
+/// reachable from these). Each graph is read once, then searched once per core type, in
+/// the order in which core types were provided. Output rows end with the core type.
+pub struct MultiCoreTransformer {
+    pub transformers: Vec<Transformer>,
+}
+impl MultiCoreTransformer {
+    /// splits a typespec into one typespec per core type. Each consists of the relations
+    /// whose source is the core type, followed by the relations between non-core types
+    /// whose source is (transitively) a target of these. Every relation must belong to at
+    /// least one of the resulting typespecs.
+    pub fn split_typespec(
+        typespec: &[Vec<String>],
+        core_types: &[String],
+    ) -> CLQResult<Vec<Vec<Vec<String>>>> {
+        let mut typespecs: Vec<Vec<Vec<String>>> = Vec::new();
+        let mut is_used: Vec<bool> = vec![false; typespec.len()];
+        for core_type in core_types {
+            let mut core_typespec: Vec<Vec<String>> = Vec::new();
+            let mut is_included: Vec<bool> = vec![false; typespec.len()];
+            // relations from the core type come first, then non-core relations are added
+            // until none are left whose source is one of the included target types.
+            loop {
+                let mut num_added: usize = 0;
+                for (ix, item) in typespec.iter().enumerate() {
+                    let source_type: &String = &item[0];
+                    let is_reachable: bool = source_type == core_type
+                        || (!core_types.contains(source_type)
+                            && core_typespec.iter().any(|x| &x[2] == source_type));
+                    if !is_included[ix] && is_reachable {
+                        core_typespec.push(item.clone());
+                        is_included[ix] = true;
+                        is_used[ix] = true;
+                        num_added += 1;
+                    }
+                }
+                if num_added == 0 {
+                    break;
+                }
+            }
+            if core_typespec.is_empty() {
+                return Err(CLQError::from(format!(
+                    "No relations found for core type: {}",
+                    core_type
+                )));
+            }
+            typespecs.push(core_typespec);
+        }
+        if let Some(ix) = is_used.iter().position(|&used| !used) {
+            return Err(CLQError::from(format!(
+                "Relation {:?} is not reachable from any core type.",
+                typespec[ix]
+            )));
+        }
+        Ok(typespecs)
+    }
+
+    /// Called by main.rs module, if several (comma-separated) core types are provided. All
+    /// transformers share the other command-line arguments, as well as the trace and drift
+    /// files (if any).
+    pub fn from_argmatches(matches: ArgMatches) -> CLQResult<Self> {
+        let typespec_str: &str = get_required_arg(&matches, "typespec")?;
+        let typespec: Vec<Vec<String>> = serde_json::from_str(typespec_str)?;
+        let core_types: Vec<String> = get_required_arg(&matches, "core_type")?
+            .split(',')
+            .map(|x| x.trim().to_string())
+            .collect();
+        let tracer = match matches.value_of("trace_file") {
+            Some(path) => Some(Arc::new(JsonLinesTraceSink::from_file(path)?)),
+            None => None,
+        };
+        let drift_sink = match matches.value_of("drift_file") {
+            Some(path) => Some(Arc::new(JsonLinesTraceSink::from_file(path)?)),
+            None => None,
+        };
+        let typespecs = MultiCoreTransformer::split_typespec(&typespec, &core_types)?;
+        let mut transformers: Vec<Transformer> = Vec::new();
+        for (core_type, core_typespec) in core_types.into_iter().zip(typespecs) {
+            let mut transformer =
+                Transformer::from_argmatches_with_types(&matches, core_typespec, core_type)?;
+            transformer.core_type_column = true;
+            if let Some(tracer) = &tracer {
+                transformer.tracer = Some(Box::new(tracer.clone()));
+            }
+            if let Some(drift_sink) = &drift_sink {
+                transformer.drift_sink = Some(Box::new(drift_sink.clone()));
+            }
+            transformers.push(transformer);
+        }
+        Ok(Self { transformers })
+    }
+
+    /// whether a line of input concerns `transformer`: edge rows must come from its core type,
+    /// or from the source type of one of its relations between non-core types, and clique
+    /// rows must be of one of its types.
+    fn is_relevant(transformer: &Transformer, line: &str) -> bool {
+        let vec: Vec<&str> = line.split('\t').collect();
+        let (node_type, is_edge_row): (&str, bool) = match vec.get(3) {
+            Some(source_type) if !source_type.is_empty() => (source_type.trim_end(), true),
+            _ => (vec.get(2).map_or("", |x| x.trim_end()), false),
+        };
+        if node_type == transformer.core_type {
+            return true;
+        }
+        let type_id: NodeTypeId = match transformer.non_core_type_ids.require(node_type) {
+            Ok(&type_id) => type_id,
+            Err(_) => return false,
+        };
+        match is_edge_row {
+            true => transformer
+                .non_core_relations
+                .iter()
+                .any(|&(source_type, _)| source_type == type_id),
+            false => true,
+        }
+    }
+
+    /// searches a graph once per core type, using the rows relevant to each.
+    fn process_graph_lines<TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+        &self,
+        graph_id: GraphId,
+        lines: &[String],
+        output: &mut Output,
+    ) -> CLQResult<()> {
+        for transformer in &self.transformers {
+            let mut edge_rows: Vec<EdgeRow> = Vec::new();
+            let mut hyperedge_rows: Vec<HyperedgeRow> = Vec::new();
+            let mut clique_rows: Vec<CliqueRow> = Vec::new();
+            for line in lines {
+                if !MultiCoreTransformer::is_relevant(transformer, line) {
+                    continue;
+                }
+                let row: Box<dyn Row> = transformer.process_line(line.clone())?;
+                if let Some(r) = row.as_edge_row() {
+                    edge_rows.push(r)
+                }
+                if let Some(r) = row.as_hyperedge_row() {
+                    hyperedge_rows.push(r)
+                }
+                if let Some(r) = row.as_clique_row() {
+                    clique_rows.push(r)
+                }
+            }
+            if transformer.hypergraph {
+                if !hyperedge_rows.is_empty() {
+                    transformer.process_top_k_hyperedge_cliques(
+                        &hyperedge_rows,
+                        clique_rows,
+                        graph_id,
+                        // verbose
+                        transformer.debug,
+                        output,
+                    )?;
+                }
+                continue;
+            }
+            if edge_rows.is_empty() {
+                continue;
+            }
+            transformer.process_top_k_cliques::<TGraphBuilder, TGraph>(
+                &edge_rows,
+                clique_rows,
+                graph_id,
+                // verbose
+                transformer.debug,
+                output,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// same as `Transformer::run`, except that graphs are processed sequentially (setting
+    /// `num_threads` is an error).
+    pub fn run<TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+        &self,
+        input: Input,
+        output: &mut Output,
+    ) -> CLQResult<()> {
+        if self.transformers.iter().any(|x| x.num_threads > 1) {
+            return Err("Graphs cannot be processed in parallel with several core types.".into());
+        }
+        // all transformers share the input spec.
+        self.transformers
+            .first()
+            .ok_or_else(CLQError::err_none)?
+            .read_graph_lines(input, |graph_id, lines| {
+                self.process_graph_lines::<TGraphBuilder, TGraph>(graph_id, &lines, output)
+            })
+    }
+}
@@ -11,18 +11,30 @@ use std::hash::{Hash, Hasher};
 use crate::dachshund::error::{CLQError, CLQResult};
 use crate::dachshund::id_types::{EdgeTypeId, NodeId, NodeTypeId};
 
-/// Used to indicate a typed edge leading to the neighbor of a node.
+/// Used to indicate a typed (and possibly weighted, or timestamped) edge leading to the
+/// neighbor of a node.
 pub struct NodeEdge {
     pub edge_type: EdgeTypeId,
     pub target_id: NodeId,
+    pub weight: f32,
+    pub timestamp: Option<i64>,
 }
 impl NodeEdge {
     pub fn new(edge_type: EdgeTypeId, target_id: NodeId) -> Self {
+        Self::with_weight(edge_type, target_id, 1.0)
+    }
+    pub fn with_weight(edge_type: EdgeTypeId, target_id: NodeId, weight: f32) -> Self {
         Self {
             edge_type,
             target_id,
+            weight,
+            timestamp: None,
         }
     }
+    pub fn with_timestamp(mut self, timestamp: Option<i64>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
 }
 
 /// Core data structure used to represent a node in our graph. A node can be
@@ -73,7 +85,19 @@ impl Node {
         }
         num_ties
     }
-    /// ensures that at least thresh % of ties with nodes represented by ids in the
+    /// weighted counterpart of `count_ties_with_ids`: sums up the weights of ties with
+    /// nodes represented by ids in the HashSet. Equal to the number of ties if the graph
+    /// is unweighted.
+    pub fn sum_tie_weights_with_ids(&self, ids: &HashSet<NodeId>) -> f32 {
+        let mut weights: f32 = 0.0;
+        for ell in &self.neighbors {
+            if ids.contains(&ell.target_id) {
+                weights += ell.weight;
+            }
+        }
+        weights
+    }
+    /// ensures that at least thresh % of (weighted) ties with nodes represented by ids in the
     /// HashSet actually exist. Called by Scorer.
     pub fn get_local_thresh_score(
         &self,
@@ -81,8 +105,8 @@ impl Node {
         ids: &HashSet<NodeId>,
         total_ties: usize,
     ) -> f32 {
-        let num_ties_with_ids = self.count_ties_with_ids(ids);
-        if (num_ties_with_ids as f32) / (total_ties as f32) >= thresh {
+        let tie_weights_with_ids = self.sum_tie_weights_with_ids(ids);
+        if tie_weights_with_ids / (total_ties as f32) >= thresh {
             1.0
         } else {
             0.0
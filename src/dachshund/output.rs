@@ -5,13 +5,36 @@
  * LICENSE file in the root directory of this source tree.
  */
 // see https://stackoverflow.com/questions/36088116/how-to-do-polymorphic-io-from-either-a-file-or-stdin-in-rust
+extern crate serde_json;
+
 use std::io::Error;
 use std::io::Write;
 
+use serde_json::{Number, Value};
+
 use crate::dachshund::error::CLQResult;
 
+/// converts a score (or other f32) to a JSON number, printed as in tab-separated output
+/// (e.g. 0.1 rather than 0.10000000149011612, and 1 rather than 1.0). NaNs and infinities
+/// become null.
+pub fn json_f32(x: f32) -> Value {
+    x.to_string()
+        .parse::<Number>()
+        .map_or(Value::Null, Value::Number)
+}
+
+/// formats a JSON value as a field of tab-separated output: null becomes an empty field,
+/// and strings are printed without quotes.
+pub fn to_tsv_field(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        v => v.to_string(),
+    }
+}
+
 pub struct Output<'a> {
-    pub destination: &'a mut Vec<u8>,
+    pub destination: &'a mut (dyn Write + Send),
     is_stdout: bool,
 }
 
@@ -28,6 +51,13 @@ impl<'a> Output<'a> {
             is_stdout: false,
         }
     }
+    /// prints to any writer (e.g. a file), and fails if it does.
+    pub fn writer(destination: &'a mut (dyn Write + Send)) -> Output<'a> {
+        Output {
+            destination,
+            is_stdout: false,
+        }
+    }
     pub fn print(&mut self, text: String) -> CLQResult<()> {
         if !self.is_stdout {
             self.write_all(text.as_bytes())?;
@@ -7,7 +7,9 @@
 use crate::dachshund::id_types::{GraphId, EdgeTypeId, NodeId, NodeTypeId};
 use std::fmt;
 
-///  Used to keep track of edge row input.
+///  Used to keep track of edge row input. Weights must lie in (0, 1], and express the
+///  strength of a tie relative to the strongest possible one (1.0, the default). Timestamps
+///  are optional, and may use any unit (e.g. seconds since the epoch).
 #[derive(Copy, Clone)]
 pub struct EdgeRow {
     pub graph_id: GraphId,
@@ -16,6 +18,8 @@ pub struct EdgeRow {
     pub source_type_id: NodeTypeId,
     pub target_type_id: NodeTypeId,
     pub edge_type_id: EdgeTypeId,
+    pub weight: f32,
+    pub timestamp: Option<i64>,
 }
 impl fmt::Display for EdgeRow {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -34,6 +38,9 @@ pub struct CliqueRow {
     pub node_id: NodeId,
     // is None when node is source
     pub target_type: Option<NodeTypeId>,
+    /// identifies the clique the row belongs to, when a graph comes with several seed
+    /// cliques. Rows without a clique_id all belong to the same (default) clique.
+    pub clique_id: Option<usize>,
 }
 impl CliqueRow {
     pub fn new<T: Into<NodeId>>(
@@ -45,6 +52,20 @@ impl CliqueRow {
             graph_id,
             node_id: node_id.into(),
             target_type,
+            clique_id: None,
+        }
+    }
+    pub fn with_clique_id<T: Into<NodeId>>(
+        graph_id: GraphId,
+        node_id: T,
+        target_type: Option<NodeTypeId>,
+        clique_id: usize,
+    ) -> Self {
+        Self {
+            graph_id,
+            node_id: node_id.into(),
+            target_type,
+            clique_id: Some(clique_id),
         }
     }
 }
@@ -61,6 +82,18 @@ impl SimpleEdgeRow {
         (self.source_id.value(), self.target_id.value())
     }
 }
+/// used to keep track of row input for typed hypergraphs: one hyperedge (e.g. an event)
+/// tying a core node to several non-core nodes at once. `non_core_type_ids` holds the
+/// type of each node in `non_core_ids`.
+#[derive(Clone)]
+pub struct HyperedgeRow {
+    pub graph_id: GraphId,
+    pub core_id: NodeId,
+    pub non_core_ids: Vec<NodeId>,
+    pub non_core_type_ids: Vec<NodeTypeId>,
+    pub edge_type_id: EdgeTypeId,
+    pub weight: f32,
+}
 /// Used in lieu of a union type. All rows processed by a Transformer
 /// must implement this trait.
 pub trait Row {
@@ -69,6 +102,9 @@ pub trait Row {
     fn as_edge_row(&self) -> Option<EdgeRow>;
     fn as_clique_row(&self) -> Option<CliqueRow>;
     fn as_simple_edge_row(&self) -> Option<SimpleEdgeRow>;
+    fn as_hyperedge_row(&self) -> Option<HyperedgeRow> {
+        None
+    }
 }
 impl Row for EdgeRow {
     fn get_graph_id(&self) -> GraphId {
@@ -112,3 +148,20 @@ impl Row for SimpleEdgeRow {
         Some(*self)
     }
 }
+impl Row for HyperedgeRow {
+    fn get_graph_id(&self) -> GraphId {
+        self.graph_id
+    }
+    fn as_edge_row(&self) -> Option<EdgeRow> {
+        None
+    }
+    fn as_clique_row(&self) -> Option<CliqueRow> {
+        None
+    }
+    fn as_simple_edge_row(&self) -> Option<SimpleEdgeRow> {
+        None
+    }
+    fn as_hyperedge_row(&self) -> Option<HyperedgeRow> {
+        Some(self.clone())
+    }
+}
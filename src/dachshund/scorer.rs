@@ -4,20 +4,158 @@
  * This source code is licensed under the MIT license found in the
  * LICENSE file in the root directory of this source tree.
  */
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::str::FromStr;
+
 use crate::dachshund::candidate::Candidate;
 use crate::dachshund::error::{CLQError, CLQResult};
 use crate::dachshund::graph::GraphBase;
+use crate::dachshund::id_types::{EdgeTypeId, NodeId, NodeTypeId};
+
+/// Used to compute the "cliqueness" score of a particular candidate, i.e. the objective
+/// the search algorithm is maximizing. Candidates with a non-positive score are not
+/// considered valid (quasi-)cliques.
+pub trait Scorer<TGraph: GraphBase> {
+    fn score(&self, candidate: &Candidate<TGraph>) -> CLQResult<f32>;
+
+    /// returns an upper bound on the score of any candidate that can be obtained by adding
+    /// at most `num_core_ids` core nodes and `num_non_core_ids` non-core nodes to `candidate`.
+    /// Used to prune exact searches. Returns None if no such bound is known.
+    fn get_upper_bound(
+        &self,
+        _candidate: &Candidate<TGraph>,
+        _num_core_ids: usize,
+        _num_non_core_ids: usize,
+    ) -> CLQResult<Option<f32>> {
+        Ok(None)
+    }
+
+    /// breaks the score of a candidate down into named components (e.g. for tracing).
+    /// Returns an empty vector if no such breakdown is available.
+    fn get_score_components(
+        &self,
+        _candidate: &Candidate<TGraph>,
+    ) -> CLQResult<Vec<(&'static str, f32)>> {
+        Ok(Vec::new())
+    }
+
+    /// returns the size constraints enforced by the scorer, if any. Searches use these
+    /// to avoid growing candidates past the maxima.
+    fn get_size_constraints(&self) -> Option<&SizeConstraints> {
+        None
+    }
+}
 
-/// Used to compute the "cliqueness" score of a particular candidate.
-pub struct Scorer {
+/// Lets a scorer be shared by a search and the code reporting on its results.
+impl<TGraph: GraphBase, T: Scorer<TGraph> + ?Sized> Scorer<TGraph> for Rc<T> {
+    fn score(&self, candidate: &Candidate<TGraph>) -> CLQResult<f32> {
+        (**self).score(candidate)
+    }
+    fn get_upper_bound(
+        &self,
+        candidate: &Candidate<TGraph>,
+        num_core_ids: usize,
+        num_non_core_ids: usize,
+    ) -> CLQResult<Option<f32>> {
+        (**self).get_upper_bound(candidate, num_core_ids, num_non_core_ids)
+    }
+    fn get_score_components(
+        &self,
+        candidate: &Candidate<TGraph>,
+    ) -> CLQResult<Vec<(&'static str, f32)>> {
+        (**self).get_score_components(candidate)
+    }
+    fn get_size_constraints(&self) -> Option<&SizeConstraints> {
+        (**self).get_size_constraints()
+    }
+}
+
+/// Identifies one of the built-in `Scorer` implementations (e.g. from the command line).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScorerType {
+    Default,
+    NonCoreCount,
+}
+impl ScorerType {
+    /// Creates a new scorer of this type, with the parameters described in `DefaultScorer::new`.
+    pub fn build<TGraph: GraphBase>(
+        &self,
+        num_non_core_types: usize,
+        alpha: f32,
+        global_thresh: Option<f32>,
+        local_thresh: Option<f32>,
+    ) -> Box<dyn Scorer<TGraph>> {
+        self.build_with_constraints(
+            num_non_core_types,
+            alpha,
+            global_thresh,
+            local_thresh,
+            SizeConstraints::default(),
+            Vec::new(),
+            Vec::new(),
+            TimeWindow::default(),
+        )
+    }
+    /// Same as `build`, but the scorer also enforces `size_constraints`,
+    /// `edge_type_constraints` and `time_window`, and measures density over the typed
+    /// subgraph induced by each candidate if there are `non_core_relations`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build_with_constraints<TGraph: GraphBase>(
+        &self,
+        num_non_core_types: usize,
+        alpha: f32,
+        global_thresh: Option<f32>,
+        local_thresh: Option<f32>,
+        size_constraints: SizeConstraints,
+        edge_type_constraints: Vec<EdgeTypeConstraint>,
+        non_core_relations: Vec<(NodeTypeId, NodeTypeId)>,
+        time_window: TimeWindow,
+    ) -> Box<dyn Scorer<TGraph>> {
+        match self {
+            ScorerType::Default => Box::new(
+                DefaultScorer::new(num_non_core_types, alpha, global_thresh, local_thresh)
+                    .with_size_constraints(size_constraints)
+                    .with_edge_type_constraints(edge_type_constraints)
+                    .with_non_core_relations(non_core_relations)
+                    .with_time_window(time_window),
+            ),
+            ScorerType::NonCoreCount => Box::new(
+                NonCoreCountScorer::new(num_non_core_types, alpha, global_thresh, local_thresh)
+                    .with_size_constraints(size_constraints)
+                    .with_edge_type_constraints(edge_type_constraints)
+                    .with_non_core_relations(non_core_relations)
+                    .with_time_window(time_window),
+            ),
+        }
+    }
+}
+impl FromStr for ScorerType {
+    type Err = CLQError;
+    fn from_str(s: &str) -> CLQResult<Self> {
+        match s {
+            "default" => Ok(ScorerType::Default),
+            "non_core_count" => Ok(ScorerType::NonCoreCount),
+            _ => Err(CLQError::from(format!("Unknown scorer: {}", s))),
+        }
+    }
+}
+
+/// The default objective: rewards the number of core nodes, the diversity of non-core
+/// types and the density of ties, subject to (optional) density thresholds.
+pub struct DefaultScorer {
     num_non_core_types: usize,
     alpha: f32,
     global_thresh: Option<f32>,
     local_thresh: Option<f32>,
+    size_constraints: SizeConstraints,
+    edge_type_constraints: Vec<EdgeTypeConstraint>,
+    non_core_relations: Vec<(NodeTypeId, NodeTypeId)>,
+    time_window: TimeWindow,
 }
 
-impl Scorer {
-    /// Creates a new Scorer class. Typically called by the `Beam` "searcher" class,
+impl DefaultScorer {
+    /// Creates a new DefaultScorer class. Typically called by the `Transformer` class,
     /// with the following parameters:
     /// - `num_non_core_types`: the number of non-core types in the graph.
     /// - `alpha`: Controls the contribution of density to the ``cliqueness'' score. Higher 
@@ -31,38 +169,105 @@ impl Scorer {
         alpha: f32,
         global_thresh: Option<f32>,
         local_thresh: Option<f32>,
-    ) -> Scorer {
-        Scorer {
+    ) -> DefaultScorer {
+        DefaultScorer {
             num_non_core_types,
             alpha,
             global_thresh,
             local_thresh,
+            size_constraints: SizeConstraints::default(),
+            edge_type_constraints: Vec::new(),
+            non_core_relations: Vec::new(),
+            time_window: TimeWindow::default(),
         }
     }
-    // computes "cliqueness" score, i.e. the objective the search algorithm is maximizing.
-    pub fn score<TGraph: GraphBase>(&self, candidate: &Candidate<TGraph>) -> CLQResult<f32> {
-        // degenerate case where there are no edges.
-        if candidate.core_ids.is_empty() || candidate.non_core_ids.is_empty() {
-            return Ok(-1.0);
+
+    /// Makes the scorer account for ties between non-core nodes, along `non_core_relations`
+    /// (see `get_cliqueness`).
+    pub fn with_non_core_relations(
+        mut self,
+        non_core_relations: Vec<(NodeTypeId, NodeTypeId)>,
+    ) -> Self {
+        self.non_core_relations = non_core_relations;
+        self
+    }
+
+    /// the (weighted) density of ties rewarded by the scorer: the hyperedge-based cliqueness
+    /// if the graph is a hypergraph (see `Candidate::get_hyperedge_cliqueness`), the density
+    /// of the typed subgraph induced by the candidate if there are relations between
+    /// non-core types, and the density of ties between core and non-core members otherwise.
+    pub fn get_cliqueness<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+    ) -> CLQResult<f32> {
+        if let Some(cliqueness) = candidate.get_hyperedge_cliqueness()? {
+            return Ok(cliqueness);
         }
-        // the more core nodes we have, the better
-        let mut score = (candidate.core_ids.len() as f32 + 1.0).ln();
+        match self.non_core_relations.is_empty() {
+            true => candidate.get_cliqueness(),
+            false => candidate.get_typed_cliqueness(&self.non_core_relations),
+        }
+    }
 
-        // the more diverse the non-core types, the better
-        let non_core_diversity_score = self.get_non_core_diversity_score(candidate)?;
-        score += non_core_diversity_score;
+    /// Makes the scorer enforce `edge_type_constraints` (see `get_edge_type_thresh_score`).
+    pub fn with_edge_type_constraints(
+        mut self,
+        edge_type_constraints: Vec<EdgeTypeConstraint>,
+    ) -> Self {
+        self.edge_type_constraints = edge_type_constraints;
+        self
+    }
 
-        // the denser the ties, the better
-        let cliqueness: f32 = candidate.get_cliqueness()?;
-        score += cliqueness * self.alpha;
+    /// Makes the scorer enforce `size_constraints` (see `get_size_constrained_score`).
+    pub fn with_size_constraints(mut self, size_constraints: SizeConstraints) -> Self {
+        self.size_constraints = size_constraints;
+        self
+    }
 
-        // enforce a minimum density threshold on cliqueness (1.0 for true cliques)
-        score *= self.get_global_thresh_score(cliqueness);
+    /// Makes the scorer enforce `time_window` (see `get_time_window_thresh_score` and
+    /// `get_time_span_penalty`).
+    pub fn with_time_window(mut self, time_window: TimeWindow) -> Self {
+        self.time_window = time_window;
+        self
+    }
 
-        // enforce a minimum density threshold for each core node.
-        score *= self.get_local_thresh_score(candidate);
+    /// candidates violating the size constraints get a negative score, which still increases
+    /// with the score they would otherwise get, so that the search can grow them towards the
+    /// minimum sizes.
+    pub fn get_size_constrained_score<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+        score: f32,
+    ) -> CLQResult<f32> {
+        if score < 0.0 || self.size_constraints.is_satisfied_by(candidate)? {
+            return Ok(score);
+        }
+        Ok(-1.0 / (1.0 + score))
+    }
 
-        Ok(score)
+    /// if size constraints are set, records whether the candidate satisfies them (1.0) or
+    /// not (0.0) as a score component.
+    fn push_size_component<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+        components: &mut Vec<(&'static str, f32)>,
+    ) -> CLQResult<()> {
+        if !self.size_constraints.is_empty() {
+            let is_satisfied: bool = self.size_constraints.is_satisfied_by(candidate)?;
+            components.push(("size", is_satisfied as i64 as f32));
+        }
+        Ok(())
+    }
+
+    /// if a time span penalty is set, records it as a score component.
+    fn push_time_span_component<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+        components: &mut Vec<(&'static str, f32)>,
+    ) {
+        if self.time_window.span_penalty != 0.0 {
+            components.push(("time_span_penalty", self.get_time_span_penalty(candidate)));
+        }
     }
 
     pub fn get_global_thresh_score(&self, cliqueness: f32) -> f32 {
@@ -78,24 +283,10 @@ impl Scorer {
                 if thresh == 0.0 {
                     return 1.0;
                 }
-                let total_size = candidate
-                    .non_core_ids
-                    .iter()
-                    .map(|&id| {
-                        candidate
-                            .get_node(id)
-                            .max_edge_count_with_core_node()
-                            .unwrap()
-                            .unwrap()
-                    })
-                    .sum();
+                let total_size = candidate.get_max_core_edge_count() as f32;
                 for &node_id in &candidate.core_ids {
-                    let score = candidate.get_node(node_id).get_local_thresh_score(
-                        thresh,
-                        &candidate.non_core_ids,
-                        total_size,
-                    );
-                    if score == 0.0 {
+                    let tie_weight = candidate.get_member_tie_weight(node_id).unwrap();
+                    if tie_weight / total_size < thresh {
                         return 0.0;
                     }
                 }
@@ -104,6 +295,70 @@ impl Scorer {
             None => 1.0,
         }
     }
+    // used to ensure that ties of given edge types are dense enough (e.g. that each core
+    // node has a tie of a given type to each non-core node of a given type).
+    pub fn get_edge_type_thresh_score<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+    ) -> CLQResult<f32> {
+        for constraint in &self.edge_type_constraints {
+            let density = candidate
+                .get_edge_type_density(constraint.edge_type, constraint.non_core_type)?;
+            if let Some(d) = density {
+                if d < constraint.min_density {
+                    return Ok(0.0);
+                }
+            }
+        }
+        Ok(1.0)
+    }
+    // used to ensure that the ties between members fall within a given time window.
+    pub fn get_time_window_thresh_score<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+    ) -> f32 {
+        match (self.time_window.max_span, candidate.get_time_span()) {
+            (Some(max_span), Some((first, last))) => (last - first <= max_span) as i64 as f32,
+            _ => 1.0,
+        }
+    }
+    /// the amount subtracted from the score of a candidate, as per the time span of the
+    /// ties between its members (0.0 if none of them is timestamped).
+    pub fn get_time_span_penalty<TGraph: GraphBase>(&self, candidate: &Candidate<TGraph>) -> f32 {
+        match candidate.get_time_span() {
+            Some((first, last)) => self.time_window.span_penalty * (last - first) as f32,
+            None => 0.0,
+        }
+    }
+    /// subtracts the time span penalty from `score`, such that, like thresholds, it can at
+    /// most zero out the score (and valid candidates are not mistaken for ones violating
+    /// the size constraints).
+    pub fn apply_time_span_penalty<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+        score: f32,
+    ) -> f32 {
+        if score <= 0.0 {
+            return score;
+        }
+        (score - self.get_time_span_penalty(candidate)).max(0.0)
+    }
+    /// combines all the constraints a candidate must satisfy into a single score,
+    /// which is 1.0 if the candidate satisfies them all, and 0.0 otherwise.
+    pub fn get_thresh_score<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+        cliqueness: f32,
+    ) -> CLQResult<f32> {
+        // enforce a minimum density threshold on cliqueness (1.0 for true cliques)
+        Ok(self.get_global_thresh_score(cliqueness)
+            // enforce a minimum density threshold for each core node.
+            * self.get_local_thresh_score(candidate)
+            // enforce minimum density thresholds for some edge types.
+            * self.get_edge_type_thresh_score(candidate)?
+            // enforce a maximum time span.
+            * self.get_time_window_thresh_score(candidate))
+    }
     /// returns a non-core diversity score that is higher with more diverse non-core types.
     pub fn get_non_core_diversity_score<TGraph: GraphBase>(
         &self,
@@ -125,3 +380,305 @@ impl Scorer {
         Ok(score)
     }
 }
+impl<TGraph: GraphBase> Scorer<TGraph> for DefaultScorer {
+    // computes "cliqueness" score, i.e. the objective the search algorithm is maximizing.
+    fn score(&self, candidate: &Candidate<TGraph>) -> CLQResult<f32> {
+        // degenerate case where there are no edges.
+        if candidate.core_ids.is_empty() || candidate.non_core_ids.is_empty() {
+            return Ok(-1.0);
+        }
+        // the more core nodes we have, the better
+        let mut score = (candidate.core_ids.len() as f32 + 1.0).ln();
+
+        // the more diverse the non-core types, the better
+        let non_core_diversity_score = self.get_non_core_diversity_score(candidate)?;
+        score += non_core_diversity_score;
+
+        // the denser the ties, the better
+        let cliqueness: f32 = self.get_cliqueness(candidate)?;
+        score += cliqueness * self.alpha;
+
+        // the shorter the time span, the better
+        score = self.apply_time_span_penalty(candidate, score);
+
+        score *= self.get_thresh_score(candidate, cliqueness)?;
+
+        self.get_size_constrained_score(candidate, score)
+    }
+
+    // thresholds can only zero out the score, size constraints can only make it negative,
+    // time span penalties are non-negative, and cliqueness is at most 1.0. The non-core
+    // diversity score is maximized by always adding non-cores of the least frequent type.
+    fn get_upper_bound(
+        &self,
+        candidate: &Candidate<TGraph>,
+        num_core_ids: usize,
+        num_non_core_ids: usize,
+    ) -> CLQResult<Option<f32>> {
+        let mut non_core_counts: Vec<usize> = vec![0; self.num_non_core_types];
+        for &non_core_id in &candidate.non_core_ids {
+            let non_core_type = candidate
+                .get_node(non_core_id)
+                .non_core_type
+                .ok_or_else(CLQError::err_none)?;
+            non_core_counts[non_core_type.value() - 1] += 1;
+        }
+        if !non_core_counts.is_empty() {
+            for _ in 0..num_non_core_ids {
+                *non_core_counts.iter_mut().min().unwrap() += 1;
+            }
+        }
+        let mut bound = (candidate.core_ids.len() as f32 + num_core_ids as f32 + 1.0).ln();
+        for non_core_count in non_core_counts {
+            bound += (non_core_count as f32 + 1.0).ln();
+        }
+        bound += self.alpha.max(0.0);
+        Ok(Some(bound.max(0.0)))
+    }
+
+    fn get_score_components(
+        &self,
+        candidate: &Candidate<TGraph>,
+    ) -> CLQResult<Vec<(&'static str, f32)>> {
+        if candidate.core_ids.is_empty() || candidate.non_core_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let cliqueness: f32 = self.get_cliqueness(candidate)?;
+        let mut components = vec![
+            ("core_size", (candidate.core_ids.len() as f32 + 1.0).ln()),
+            ("non_core_diversity", self.get_non_core_diversity_score(candidate)?),
+            ("cliqueness", cliqueness),
+            ("thresh", self.get_thresh_score(candidate, cliqueness)?),
+        ];
+        self.push_size_component(candidate, &mut components)?;
+        self.push_time_span_component(candidate, &mut components);
+        Ok(components)
+    }
+
+    fn get_size_constraints(&self) -> Option<&SizeConstraints> {
+        match self.size_constraints.is_empty() {
+            true => None,
+            false => Some(&self.size_constraints),
+        }
+    }
+}
+
+/// Favours candidates with many non-core nodes, regardless of their types: the non-core
+/// diversity term of the default objective is replaced by ln(#non-core + 1). Candidates
+/// are subject to the same thresholds as with the `DefaultScorer`.
+pub struct NonCoreCountScorer {
+    alpha: f32,
+    default_scorer: DefaultScorer,
+}
+impl NonCoreCountScorer {
+    /// Takes the same parameters as `DefaultScorer::new`.
+    pub fn new(
+        num_non_core_types: usize,
+        alpha: f32,
+        global_thresh: Option<f32>,
+        local_thresh: Option<f32>,
+    ) -> NonCoreCountScorer {
+        NonCoreCountScorer {
+            alpha,
+            default_scorer: DefaultScorer::new(
+                num_non_core_types,
+                alpha,
+                global_thresh,
+                local_thresh,
+            ),
+        }
+    }
+
+    /// Makes the scorer enforce `size_constraints`, as per `DefaultScorer`.
+    pub fn with_size_constraints(mut self, size_constraints: SizeConstraints) -> Self {
+        self.default_scorer = self.default_scorer.with_size_constraints(size_constraints);
+        self
+    }
+
+    /// Makes the scorer enforce `edge_type_constraints`, as per `DefaultScorer`.
+    pub fn with_edge_type_constraints(
+        mut self,
+        edge_type_constraints: Vec<EdgeTypeConstraint>,
+    ) -> Self {
+        self.default_scorer = self
+            .default_scorer
+            .with_edge_type_constraints(edge_type_constraints);
+        self
+    }
+
+    /// Makes the scorer enforce `time_window`, as per `DefaultScorer`.
+    pub fn with_time_window(mut self, time_window: TimeWindow) -> Self {
+        self.default_scorer = self.default_scorer.with_time_window(time_window);
+        self
+    }
+
+    /// Makes the scorer account for ties between non-core nodes, as per `DefaultScorer`.
+    pub fn with_non_core_relations(
+        mut self,
+        non_core_relations: Vec<(NodeTypeId, NodeTypeId)>,
+    ) -> Self {
+        self.default_scorer = self.default_scorer.with_non_core_relations(non_core_relations);
+        self
+    }
+}
+impl<TGraph: GraphBase> Scorer<TGraph> for NonCoreCountScorer {
+    fn score(&self, candidate: &Candidate<TGraph>) -> CLQResult<f32> {
+        if candidate.core_ids.is_empty() || candidate.non_core_ids.is_empty() {
+            return Ok(-1.0);
+        }
+        let mut score = (candidate.core_ids.len() as f32 + 1.0).ln();
+        score += (candidate.non_core_ids.len() as f32 + 1.0).ln();
+        let cliqueness: f32 = self.default_scorer.get_cliqueness(candidate)?;
+        score += cliqueness * self.alpha;
+        score = self.default_scorer.apply_time_span_penalty(candidate, score);
+        score *= self.default_scorer.get_thresh_score(candidate, cliqueness)?;
+        self.default_scorer.get_size_constrained_score(candidate, score)
+    }
+
+    fn get_upper_bound(
+        &self,
+        candidate: &Candidate<TGraph>,
+        num_core_ids: usize,
+        num_non_core_ids: usize,
+    ) -> CLQResult<Option<f32>> {
+        let mut bound = (candidate.core_ids.len() as f32 + num_core_ids as f32 + 1.0).ln();
+        bound += (candidate.non_core_ids.len() as f32 + num_non_core_ids as f32 + 1.0).ln();
+        bound += self.alpha.max(0.0);
+        Ok(Some(bound.max(0.0)))
+    }
+
+    fn get_score_components(
+        &self,
+        candidate: &Candidate<TGraph>,
+    ) -> CLQResult<Vec<(&'static str, f32)>> {
+        if candidate.core_ids.is_empty() || candidate.non_core_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let cliqueness: f32 = self.default_scorer.get_cliqueness(candidate)?;
+        let mut components = vec![
+            ("core_size", (candidate.core_ids.len() as f32 + 1.0).ln()),
+            ("non_core_size", (candidate.non_core_ids.len() as f32 + 1.0).ln()),
+            ("cliqueness", cliqueness),
+            (
+                "thresh",
+                self.default_scorer.get_thresh_score(candidate, cliqueness)?,
+            ),
+        ];
+        self.default_scorer.push_size_component(candidate, &mut components)?;
+        self.default_scorer.push_time_span_component(candidate, &mut components);
+        Ok(components)
+    }
+
+    fn get_size_constraints(&self) -> Option<&SizeConstraints> {
+        Scorer::<TGraph>::get_size_constraints(&self.default_scorer)
+    }
+}
+
+/// Requires the (weighted) density of ties of type `edge_type`, between core nodes and
+/// non-core nodes of type `non_core_type`, to be at least `min_density` (1.0 requires each
+/// core node to have such a tie to each such non-core node).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EdgeTypeConstraint {
+    pub edge_type: EdgeTypeId,
+    pub non_core_type: NodeTypeId,
+    pub min_density: f32,
+}
+
+/// Constraints on the time span of the (timestamped) ties between the members of valid
+/// (quasi-)cliques, in the units of the input timestamps. Ties without timestamps are not
+/// constrained.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TimeWindow {
+    /// if provided, the ties must all fall within a window of at most this length.
+    pub max_span: Option<i64>,
+    /// subtracted from the score, per unit of time spanned by the ties.
+    pub span_penalty: f32,
+}
+impl TimeWindow {
+    /// checks that neither the max span nor the penalty is negative (scorers bound scores
+    /// from above assuming penalties can only lower them).
+    pub fn new(max_span: Option<i64>, span_penalty: f32) -> CLQResult<Self> {
+        if matches!(max_span, Some(x) if x < 0) {
+            return Err(CLQError::from("Max time span must be non-negative."));
+        }
+        if span_penalty < 0.0 || span_penalty.is_nan() {
+            return Err(CLQError::from("Time span penalty must be non-negative."));
+        }
+        Ok(Self {
+            max_span,
+            span_penalty,
+        })
+    }
+}
+
+/// An (inclusive) range of allowed sizes. `max` is unbounded if None.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SizeRange {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+impl SizeRange {
+    pub fn new(min: usize, max: Option<usize>) -> Self {
+        Self { min, max }
+    }
+    pub fn contains(&self, size: usize) -> bool {
+        size >= self.min && !self.is_exceeded_by(size)
+    }
+    pub fn is_exceeded_by(&self, size: usize) -> bool {
+        match self.max {
+            Some(max) => size > max,
+            None => false,
+        }
+    }
+}
+
+/// Minimum and maximum sizes of the core side, of the non-core side, and of each
+/// non-core type, that valid (quasi-)cliques must have.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SizeConstraints {
+    pub core: SizeRange,
+    pub non_core: SizeRange,
+    pub non_core_types: HashMap<NodeTypeId, SizeRange>,
+}
+impl SizeConstraints {
+    /// whether no constraint is set, i.e., all candidates satisfy these constraints.
+    pub fn is_empty(&self) -> bool {
+        self.core == SizeRange::default()
+            && self.non_core == SizeRange::default()
+            && self.non_core_types.values().all(|x| *x == SizeRange::default())
+    }
+    /// whether the size of each side, and of each non-core type, is within range.
+    pub fn is_satisfied_by<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+    ) -> CLQResult<bool> {
+        if !self.core.contains(candidate.core_ids.len())
+            || !self.non_core.contains(candidate.non_core_ids.len())
+        {
+            return Ok(false);
+        }
+        Ok(self
+            .non_core_types
+            .iter()
+            .all(|(t, range)| range.contains(candidate.count_non_core_type(*t))))
+    }
+    /// whether adding `node_id` to `candidate` would take it past any of the maxima.
+    pub fn is_exceeded_by_adding<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+        node_id: NodeId,
+    ) -> CLQResult<bool> {
+        let node = candidate.get_node(node_id);
+        if node.is_core() {
+            return Ok(self.core.is_exceeded_by(candidate.core_ids.len() + 1));
+        }
+        if self.non_core.is_exceeded_by(candidate.non_core_ids.len() + 1) {
+            return Ok(true);
+        }
+        let non_core_type = node.non_core_type.ok_or_else(CLQError::err_none)?;
+        Ok(match self.non_core_types.get(&non_core_type) {
+            Some(range) => range.is_exceeded_by(candidate.count_non_core_type(non_core_type) + 1),
+            None => false,
+        })
+    }
+}
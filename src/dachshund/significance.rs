@@ -0,0 +1,176 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate rand;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use rand::prelude::*;
+
+use crate::dachshund::candidate::Candidate;
+use crate::dachshund::error::{CLQError, CLQResult};
+use crate::dachshund::graph::{GraphBase, GraphBuilder};
+use crate::dachshund::id_types::{EdgeTypeId, GraphId, NodeId, NodeTypeId};
+use crate::dachshund::row::EdgeRow;
+use crate::dachshund::scorer::Scorer;
+
+/// How surprising a (quasi-)clique's score is, relative to the scores of the same node set
+/// in randomly rewired versions of the graph.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Significance {
+    pub score: f32,
+    pub null_mean: f32,
+    pub null_std: f32,
+    /// fraction of rewired graphs (counting the observed graph itself) in which the node set
+    /// scores at least as well as in the observed graph.
+    pub p_value: f32,
+    /// None if the null scores have no variance.
+    pub z_score: Option<f32>,
+}
+
+/// A null model preserving the degree sequence of the graph: each sample is obtained by
+/// repeatedly picking two edges of the same edge type and non-core type, and swapping
+/// their non-core endpoints (unless this would duplicate an existing edge). Core nodes
+/// thus stay connected to non-core nodes of the same type, through the same edge types.
+/// Ties between non-core nodes are not supported.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NullModel {
+    /// number of rewired graphs to sample.
+    pub num_samples: usize,
+    /// number of swaps attempted per sample, as a multiple of the number of edges.
+    pub swaps_per_edge: usize,
+}
+
+impl NullModel {
+    pub fn new(num_samples: usize) -> Self {
+        Self {
+            num_samples,
+            swaps_per_edge: 10,
+        }
+    }
+
+    /// recovers the edge rows a typed graph was built from (core -> non-core), in a
+    /// canonical order. `core_type_id` is used as the type of all source nodes.
+    pub fn get_edge_rows<TGraph: GraphBase>(
+        graph: &TGraph,
+        graph_id: GraphId,
+        core_type_id: NodeTypeId,
+    ) -> CLQResult<Vec<EdgeRow>> {
+        let mut rows: Vec<EdgeRow> = Vec::new();
+        for &core_id in graph.get_core_ids() {
+            for edge in &graph.get_node(core_id).neighbors {
+                rows.push(EdgeRow {
+                    graph_id,
+                    source_id: core_id,
+                    target_id: edge.target_id,
+                    source_type_id: core_type_id,
+                    target_type_id: graph
+                        .get_node(edge.target_id)
+                        .non_core_type
+                        .ok_or_else(CLQError::err_none)?,
+                    edge_type_id: edge.edge_type,
+                    weight: edge.weight,
+                    timestamp: edge.timestamp,
+                });
+            }
+        }
+        Ok(rows)
+    }
+
+    /// attempts `num_swaps` degree-preserving swaps on `rows`, in place. Returns the
+    /// number of swaps which were carried out.
+    pub fn rewire(rows: &mut [EdgeRow], num_swaps: usize, rng: &mut impl Rng) -> usize {
+        if rows.len() < 2 {
+            return 0;
+        }
+        let mut edges: HashSet<(NodeId, NodeId, EdgeTypeId)> = rows
+            .iter()
+            .map(|r| (r.source_id, r.target_id, r.edge_type_id))
+            .collect();
+        let mut num_swapped: usize = 0;
+        for _ in 0..num_swaps {
+            let i: usize = rng.gen_range(0, rows.len());
+            let j: usize = rng.gen_range(0, rows.len());
+            let (a, b) = (rows[i], rows[j]);
+            if a.edge_type_id != b.edge_type_id
+                || a.target_type_id != b.target_type_id
+                || a.source_id == b.source_id
+                || a.target_id == b.target_id
+            {
+                continue;
+            }
+            let swapped_a = (a.source_id, b.target_id, a.edge_type_id);
+            let swapped_b = (b.source_id, a.target_id, b.edge_type_id);
+            if edges.contains(&swapped_a) || edges.contains(&swapped_b) {
+                continue;
+            }
+            edges.remove(&(a.source_id, a.target_id, a.edge_type_id));
+            edges.remove(&(b.source_id, b.target_id, b.edge_type_id));
+            edges.insert(swapped_a);
+            edges.insert(swapped_b);
+            rows[i].target_id = b.target_id;
+            rows[j].target_id = a.target_id;
+            num_swapped += 1;
+        }
+        num_swapped
+    }
+
+    /// re-scores the node set of `candidate` on `self.num_samples` rewired versions of
+    /// its graph, built from `rows`. Sampling is seeded with `graph_id` (and `rng_seed`,
+    /// if provided), so that identically configured runs yield the same results.
+    pub fn get_significance<TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+        rows: &[EdgeRow],
+        graph_id: GraphId,
+        scorer: &dyn Scorer<TGraph>,
+        rng_seed: Option<u64>,
+    ) -> CLQResult<Significance> {
+        if self.num_samples == 0 {
+            return Err("The null model requires at least one sample.".into());
+        }
+        let score: f32 = candidate.get_score()?;
+        let output_rows = candidate.get_output_rows(graph_id)?;
+        let mut seeder = DefaultHasher::new();
+        graph_id.hash(&mut seeder);
+        if let Some(seed) = rng_seed {
+            seed.hash(&mut seeder);
+        }
+        let mut rng = StdRng::seed_from_u64(seeder.finish());
+
+        let mut null_scores: Vec<f32> = Vec::with_capacity(self.num_samples);
+        for _ in 0..self.num_samples {
+            let mut rewired: Vec<EdgeRow> = rows.to_vec();
+            NullModel::rewire(&mut rewired, self.swaps_per_edge * rows.len(), &mut rng);
+            // no pruning, so that all of the candidate's nodes remain in the graph.
+            let graph: TGraph = TGraphBuilder::new(graph_id, &rewired, None)?;
+            let null_candidate = Candidate::from_clique_rows(output_rows.clone(), &graph, scorer)?
+                .ok_or_else(CLQError::err_none)?;
+            null_scores.push(null_candidate.get_score()?);
+        }
+        let num_samples: f32 = null_scores.len() as f32;
+        let null_mean: f32 = null_scores.iter().sum::<f32>() / num_samples;
+        let null_std: f32 = (null_scores
+            .iter()
+            .map(|x| (x - null_mean).powi(2))
+            .sum::<f32>()
+            / num_samples)
+            .sqrt();
+        let num_as_extreme: usize = null_scores.iter().filter(|&&x| x >= score).count();
+        Ok(Significance {
+            score,
+            null_mean,
+            null_std,
+            p_value: (1 + num_as_extreme) as f32 / (1.0 + num_samples),
+            z_score: match null_std > 0.0 {
+                true => Some((score - null_mean) / null_std),
+                false => None,
+            },
+        })
+    }
+}
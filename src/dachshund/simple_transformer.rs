@@ -7,15 +7,17 @@
 extern crate clap;
 extern crate serde_json;
 
-use crate::dachshund::error::CLQResult;
+use crate::dachshund::error::{CLQError, CLQResult};
 use crate::dachshund::graph::{GraphBase, SimpleUndirectedGraph, SimpleUndirectedGraphBuilder};
 use crate::dachshund::id_types::{GraphId, NodeId};
 use crate::dachshund::input::Input;
+use crate::dachshund::input_spec::{InputSpec, LineNormalizer};
+use crate::dachshund::interner::NodeIdInterner;
 use crate::dachshund::output::Output;
 use crate::dachshund::row::{Row, SimpleEdgeRow};
 use rand::seq::SliceRandom;
 use rayon::{ThreadPool, ThreadPoolBuilder};
-use serde_json::json;
+use serde_json::{json, Value};
 use std::collections::{HashMap, HashSet};
 use std::io::prelude::*;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -26,16 +28,58 @@ use std::time::Duration;
 
 /// deals with processing lines and turning them into rows.
 /// Can mutate ids and reverse_ids maps that keep track of
-/// graph_ids seen so far.
+/// graph_ids seen so far. If a node id interner is provided, node ids
+/// can be arbitrary strings. If an input spec is provided, lines are
+/// rewritten as per the spec before being processed (see `get_line_normalizer`).
+/// Also formats output lines, as tab-separated or JSON Lines output.
 pub struct LineProcessor {
     ids: Arc<RwLock<HashMap<String, i64>>>,
     reverse_ids: Arc<RwLock<Vec<String>>>,
+    node_id_interner: Option<NodeIdInterner>,
+    input_spec: InputSpec,
+    json_lines_output: bool,
 }
 impl LineProcessor {
+    /// the columns of input lines: graph_id\tsource_id\ttarget_id
+    pub const INPUT_COLUMNS: &'static [&'static str] = &["graph_id", "source_id", "target_id"];
+
     pub fn new() -> Self {
         Self {
             ids: Arc::new(RwLock::new(HashMap::new())),
             reverse_ids: Arc::new(RwLock::new(Vec::new())),
+            node_id_interner: None,
+            input_spec: InputSpec::default(),
+            json_lines_output: false,
+        }
+    }
+    pub fn with_node_id_interner(mut self, node_id_interner: NodeIdInterner) -> Self {
+        self.node_id_interner = Some(node_id_interner);
+        self
+    }
+    pub fn with_input_spec(mut self, input_spec: InputSpec) -> Self {
+        self.input_spec = input_spec;
+        self
+    }
+    pub fn with_json_lines_output(mut self) -> Self {
+        self.json_lines_output = true;
+        self
+    }
+    /// creates a `LineNormalizer`, rewriting input lines as per the input spec.
+    pub fn get_line_normalizer(&self) -> CLQResult<LineNormalizer> {
+        self.input_spec
+            .normalizer(LineProcessor::INPUT_COLUMNS, LineProcessor::INPUT_COLUMNS.len())
+    }
+    fn parse_node_id(&self, graph_id: GraphId, s: &str) -> CLQResult<NodeId> {
+        match &self.node_id_interner {
+            Some(interner) => Ok(interner.intern(graph_id, s)),
+            None => Ok(s.parse::<i64>()?.into()),
+        }
+    }
+    /// forgets the interned node ids of a graph (if they are specific to it), once the
+    /// graph has been built.
+    pub fn release_node_ids(&self, graph_id: GraphId) {
+        if let Some(interner) = &self.node_id_interner {
+            interner.release(graph_id);
         }
     }
     fn record_new_key_or_return_current_one(&self, key: String) -> GraphId {
@@ -51,11 +95,17 @@ impl LineProcessor {
     }
     pub fn process_line(&self, line: String) -> CLQResult<Box<dyn Row>> {
         let vec: Vec<&str> = line.split('\t').collect();
-        assert!(vec.len() == 3);
+        if vec.len() != 3 {
+            return Err(CLQError::from(format!(
+                "Expected 3 tab-separated columns, got {} in line: {}",
+                vec.len(),
+                line
+            )));
+        }
         let key = vec[0].to_string();
         let graph_id = self.record_new_key_or_return_current_one(key);
-        let source_id: NodeId = vec[1].parse::<i64>()?.into();
-        let target_id: NodeId = vec[2].parse::<i64>()?.into();
+        let source_id: NodeId = self.parse_node_id(graph_id, vec[1].trim_end())?;
+        let target_id: NodeId = self.parse_node_id(graph_id, vec[2].trim_end())?;
         Ok(Box::new(SimpleEdgeRow {
             graph_id,
             source_id,
@@ -65,6 +115,19 @@ impl LineProcessor {
     pub fn get_original_id(&self, local_id: usize) -> String {
         self.reverse_ids.read().unwrap()[local_id].clone()
     }
+    /// formats the stats of a graph as an output line: the original graph_id followed by
+    /// the stats, as a JSON object, or, with JSON Lines output, the stats along with the
+    /// original graph_id (as "graph_id").
+    pub fn format_graph_stats(&self, graph_id: GraphId, mut stats: Value) -> String {
+        let original_id = self.get_original_id(graph_id.value() as usize);
+        match (self.json_lines_output, stats.as_object_mut()) {
+            (true, Some(object)) => {
+                object.insert("graph_id".into(), Value::String(original_id));
+                stats.to_string()
+            }
+            _ => format!("{}\t{}", original_id, stats),
+        }
+    }
 }
 impl Default for LineProcessor {
     fn default() -> Self {
@@ -110,14 +173,22 @@ pub trait TransformerBase {
                         output.print(line).unwrap();
                         num_processed_clone.fetch_add(1, Ordering::SeqCst);
                     }
-                    Err(error) => panic!(error),
+                    // the sender is dropped without a shutdown message if reading the
+                    // input fails, in which case the error is returned below.
+                    Err(_) => return,
                 }
             });
             let mut current_graph_id: Option<GraphId> = None;
             let mut num_to_process: usize = 0;
+            let mut normalizer: LineNormalizer = line_processor.get_line_normalizer()?;
             for line in input.lines() {
                 match line {
                     Ok(n) => {
+                        let n: String = match normalizer.normalize(n)? {
+                            Some(n) => n,
+                            // header row
+                            None => continue,
+                        };
                         let row: Box<dyn Row> = line_processor.process_line(n)?;
                         let new_graph_id: GraphId = row.get_graph_id();
                         if let Some(some_current_graph_id) = current_graph_id {
@@ -151,6 +222,9 @@ pub trait TransformerBase {
 
 pub trait GraphStatsTransformerBase: TransformerBase {
     fn compute_graph_stats_json(graph: &SimpleUndirectedGraph) -> String {
+        Self::compute_graph_stats(graph).to_string()
+    }
+    fn compute_graph_stats(graph: &SimpleUndirectedGraph) -> Value {
         let conn_comp = graph.get_connected_components();
         let largest_cc = conn_comp.iter().max_by_key(|x| x.len()).unwrap();
         let sources: Vec<NodeId> = largest_cc
@@ -189,14 +263,17 @@ pub trait GraphStatsTransformerBase: TransformerBase {
             "evcent": (Iterator::sum::<f64>(evcent.values()) / 
                 (evcent.len() as f64) * 1000.0).floor() / 1000.0,
             "clust_coef": (graph.get_avg_clustering() * 1000.0).floor() / 1000.0, 
-        }).to_string()
+        })
     }
 }
 impl SimpleTransformer {
     pub fn new() -> Self {
+        Self::with_line_processor(LineProcessor::new())
+    }
+    pub fn with_line_processor(line_processor: LineProcessor) -> Self {
         Self {
             batch: Vec::new(),
-            line_processor: Arc::new(LineProcessor::new()),
+            line_processor: Arc::new(line_processor),
         }
     }
 }
@@ -207,9 +284,12 @@ impl Default for SimpleTransformer {
 }
 impl SimpleParallelTransformer {
     pub fn new() -> Self {
+        Self::with_line_processor(LineProcessor::new())
+    }
+    pub fn with_line_processor(line_processor: LineProcessor) -> Self {
         Self {
             batch: Vec::new(),
-            line_processor: Arc::new(LineProcessor::new()),
+            line_processor: Arc::new(line_processor),
             pool: ThreadPoolBuilder::new().build().unwrap(),
         }
     }
@@ -238,12 +318,10 @@ impl TransformerBase for SimpleTransformer {
         output: &Sender<(String, bool)>,
     ) -> CLQResult<()> {
         let tuples: Vec<(i64, i64)> = self.batch.iter().map(|x| x.as_tuple()).collect();
+        self.line_processor.release_node_ids(graph_id);
         let graph = SimpleUndirectedGraphBuilder::from_vector(&tuples);
-        let stats = Self::compute_graph_stats_json(&graph);
-        let original_id = self
-            .line_processor
-            .get_original_id(graph_id.value() as usize);
-        let line: String = format!("{}\t{}", original_id, stats);
+        let stats = Self::compute_graph_stats(&graph);
+        let line: String = self.line_processor.format_graph_stats(graph_id, stats);
         output.send((line, false)).unwrap();
         Ok(())
     }
@@ -266,13 +344,13 @@ impl TransformerBase for SimpleParallelTransformer {
         output: &Sender<(String, bool)>,
     ) -> CLQResult<()> {
         let tuples: Vec<(i64, i64)> = self.batch.iter().map(|x| x.as_tuple()).collect();
+        self.line_processor.release_node_ids(graph_id);
         let output_clone = output.clone();
         let line_processor = self.line_processor.clone();
         self.pool.spawn(move || {
             let graph = SimpleUndirectedGraphBuilder::from_vector(&tuples);
-            let stats = Self::compute_graph_stats_json(&graph);
-            let original_id = line_processor.get_original_id(graph_id.value() as usize);
-            let line: String = format!("{}\t{}", original_id, stats);
+            let stats = Self::compute_graph_stats(&graph);
+            let line: String = line_processor.format_graph_stats(graph_id, stats);
             output_clone.send((line, false)).unwrap();
         });
         Ok(())
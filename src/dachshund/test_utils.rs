@@ -4,9 +4,13 @@
  * This source code is licensed under the MIT license found in the
  * LICENSE file in the root directory of this source tree.
  */
+extern crate clap;
+
 use std::collections::HashSet;
 use std::fmt::Debug;
 
+use clap::{App, Arg};
+
 use crate::dachshund::error::{CLQError, CLQResult};
 use crate::dachshund::graph::{Graph, GraphBase};
 use crate::dachshund::id_types::{GraphId, NodeId, NodeTypeId};
@@ -31,6 +35,40 @@ pub fn gen_test_transformer(typespec: Vec<Vec<String>>, core_type: String) -> CL
     Ok(transformer)
 }
 
+/// parses command-line arguments, as main.rs does, with the required ones set to defaults.
+/// Extra arguments must be of the form --name=value.
+pub fn gen_test_transformer_from_args(
+    typespec: &str,
+    extra_args: &[&str],
+) -> CLQResult<Transformer> {
+    let mut args: Vec<&str> = vec![
+        "test",
+        "--typespec",
+        typespec,
+        "--core_type=author",
+        "--beam_size=20",
+        "--alpha=1.0",
+        "--global_thresh=1.0",
+        "--local_thresh=1.0",
+        "--num_to_search=10",
+        "--epochs=10",
+        "--max_repeated_prior_scores=3",
+        "--debug_mode=false",
+        "--min_degree=1",
+        "--long_format=false",
+    ];
+    args.extend(extra_args);
+    let names: Vec<&str> = args[1..]
+        .iter()
+        .filter_map(|arg| arg.strip_prefix("--"))
+        .map(|arg| arg.split('=').next().unwrap_or(arg))
+        .collect();
+    let app = names.iter().fold(App::new("test"), |app, name| {
+        app.arg(Arg::with_name(name).long(name).takes_value(true))
+    });
+    Transformer::from_argmatches(app.get_matches_from(args))
+}
+
 pub fn gen_test_typespec() -> Vec<Vec<String>> {
     return vec![
         vec!["author".to_string(), "published_at".into(), "conference".into()],
@@ -138,3 +176,19 @@ pub fn gen_single_clique(
     );
     (core_ids, non_core_ids, clique_rows)
 }
+
+/// the edges of Zachary's karate club network (78 friendships between 34 members, numbered
+/// from 1), each listed once.
+pub fn gen_karate_club_edges() -> Vec<(usize, usize)> {
+    vec![
+        (1, 2), (1, 3), (2, 3), (1, 4), (2, 4), (3, 4), (1, 5), (1, 6), (1, 7), (5, 7), (6, 7),
+        (1, 8), (2, 8), (3, 8), (4, 8), (1, 9), (3, 9), (3, 10), (1, 11), (5, 11), (6, 11), (1, 12),
+        (1, 13), (4, 13), (1, 14), (2, 14), (3, 14), (4, 14), (6, 17), (7, 17), (1, 18), (2, 18),
+        (1, 20), (2, 20), (1, 22), (2, 22), (24, 26), (25, 26), (3, 28), (24, 28), (25, 28),
+        (3, 29), (24, 30), (27, 30), (2, 31), (9, 31), (1, 32), (25, 32), (26, 32), (29, 32),
+        (3, 33), (9, 33), (15, 33), (16, 33), (19, 33), (21, 33), (23, 33), (24, 33), (30, 33),
+        (31, 33), (32, 33), (9, 34), (10, 34), (14, 34), (15, 34), (16, 34), (19, 34), (20, 34),
+        (21, 34), (23, 34), (24, 34), (27, 34), (28, 34), (29, 34), (30, 34), (31, 34), (32, 34),
+        (33, 34),
+    ]
+}
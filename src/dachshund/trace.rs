@@ -0,0 +1,238 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate serde_json;
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Map, Value};
+
+use crate::dachshund::candidate::Candidate;
+use crate::dachshund::error::{CLQError, CLQResult};
+use crate::dachshund::graph::GraphBase;
+use crate::dachshund::id_types::{GraphId, NodeId};
+
+/// A snapshot of a candidate, as recorded in a trace. `non_core_types` holds the type
+/// of each of the non-core nodes, in the same order as `non_core_ids`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandidateSummary {
+    pub core_ids: Vec<i64>,
+    pub non_core_ids: Vec<i64>,
+    pub non_core_types: Vec<String>,
+    pub score: Option<f32>,
+}
+
+impl CandidateSummary {
+    pub fn from_candidate<TGraph: GraphBase>(
+        candidate: &Candidate<TGraph>,
+        target_types: &[String],
+    ) -> CLQResult<Self> {
+        let non_core_ids: Vec<NodeId> = candidate.sorted_non_core_ids();
+        let mut non_core_types: Vec<String> = Vec::new();
+        for &id in &non_core_ids {
+            let non_core_type = candidate
+                .get_node(id)
+                .non_core_type
+                .ok_or_else(CLQError::err_none)?;
+            non_core_types.push(target_types[non_core_type.value() - 1].clone());
+        }
+        Ok(Self {
+            core_ids: candidate.sorted_core_ids().iter().map(|x| x.value()).collect(),
+            non_core_ids: non_core_ids.iter().map(|x| x.value()).collect(),
+            non_core_types,
+            score: candidate.get_score().ok(),
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "core_ids": self.core_ids,
+            "non_core_ids": self.non_core_ids,
+            "non_core_types": self.non_core_types,
+            "score": self.score,
+        })
+    }
+}
+
+/// A structured event emitted over the course of a search.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceEvent {
+    /// an epoch of the beam search starts, with `beam_size` candidates in the beam.
+    EpochStart {
+        graph_id: GraphId,
+        epoch: usize,
+        beam_size: usize,
+    },
+    /// a candidate in the beam is considered for expansion. `visited` is true if it has
+    /// already been fully expanded, in which case it is kept as is.
+    CandidateConsidered {
+        graph_id: GraphId,
+        epoch: usize,
+        candidate: CandidateSummary,
+        visited: bool,
+    },
+    /// an expansion candidate was scored. `components` breaks the score down, as per
+    /// `Scorer::get_score_components`.
+    ExpansionScored {
+        graph_id: GraphId,
+        epoch: usize,
+        candidate: CandidateSummary,
+        components: Vec<(&'static str, f32)>,
+    },
+    /// the contents of the beam at the end of an epoch, best candidate first.
+    BeamContents {
+        graph_id: GraphId,
+        epoch: usize,
+        candidates: Vec<CandidateSummary>,
+    },
+    /// the search stopped before running for the maximum number of epochs.
+    EarlyStop {
+        graph_id: GraphId,
+        epoch: usize,
+        reason: String,
+    },
+    /// a seed clique could not be used as is (see `SeedIssue`).
+    SeedIssue {
+        graph_id: GraphId,
+        clique_id: Option<usize>,
+        issue: String,
+    },
+}
+
+impl TraceEvent {
+    /// encodes the event as a JSON object, with an "event" field identifying its kind.
+    pub fn to_json(&self) -> Value {
+        match self {
+            TraceEvent::EpochStart {
+                graph_id,
+                epoch,
+                beam_size,
+            } => json!({
+                "event": "epoch_start",
+                "graph_id": graph_id.value(),
+                "epoch": epoch,
+                "beam_size": beam_size,
+            }),
+            TraceEvent::CandidateConsidered {
+                graph_id,
+                epoch,
+                candidate,
+                visited,
+            } => json!({
+                "event": "candidate_considered",
+                "graph_id": graph_id.value(),
+                "epoch": epoch,
+                "candidate": candidate.to_json(),
+                "visited": visited,
+            }),
+            TraceEvent::ExpansionScored {
+                graph_id,
+                epoch,
+                candidate,
+                components,
+            } => {
+                let mut components_map = Map::new();
+                for (name, value) in components {
+                    components_map.insert(name.to_string(), json!(value));
+                }
+                json!({
+                    "event": "expansion_scored",
+                    "graph_id": graph_id.value(),
+                    "epoch": epoch,
+                    "candidate": candidate.to_json(),
+                    "components": components_map,
+                })
+            }
+            TraceEvent::BeamContents {
+                graph_id,
+                epoch,
+                candidates,
+            } => json!({
+                "event": "beam_contents",
+                "graph_id": graph_id.value(),
+                "epoch": epoch,
+                "candidates": candidates.iter().map(|x| x.to_json()).collect::<Vec<Value>>(),
+            }),
+            TraceEvent::EarlyStop {
+                graph_id,
+                epoch,
+                reason,
+            } => json!({
+                "event": "early_stop",
+                "graph_id": graph_id.value(),
+                "epoch": epoch,
+                "reason": reason,
+            }),
+            TraceEvent::SeedIssue {
+                graph_id,
+                clique_id,
+                issue,
+            } => json!({
+                "event": "seed_issue",
+                "graph_id": graph_id.value(),
+                "clique_id": clique_id,
+                "issue": issue,
+            }),
+        }
+    }
+}
+
+/// Receives the structured events emitted by a search. Sinks may be shared by searches
+/// running on multiple threads.
+pub trait TraceSink: Send + Sync {
+    fn record(&self, event: &TraceEvent) -> CLQResult<()>;
+}
+
+/// Lets a sink be shared by several transformers (see `MultiCoreTransformer`).
+impl<T: TraceSink + ?Sized> TraceSink for Arc<T> {
+    fn record(&self, event: &TraceEvent) -> CLQResult<()> {
+        (**self).record(event)
+    }
+}
+
+/// Writes each event as a line of JSON (see also `DriftSink`).
+pub struct JsonLinesTraceSink<W: Write + Send> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonLinesTraceSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// returns the underlying writer (e.g. to inspect a buffer).
+    pub fn into_inner(self) -> CLQResult<W> {
+        self.writer
+            .into_inner()
+            .map_err(|_| CLQError::from("Trace writer lock was poisoned."))
+    }
+
+    pub(crate) fn write_line(&self, value: &Value) -> CLQResult<()> {
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| CLQError::from("Trace writer lock was poisoned."))?;
+        writeln!(writer, "{}", value)?;
+        Ok(())
+    }
+}
+
+impl JsonLinesTraceSink<BufWriter<File>> {
+    /// creates (or truncates) the file at `path`, and writes the trace to it.
+    pub fn from_file(path: &str) -> CLQResult<Self> {
+        Ok(JsonLinesTraceSink::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write + Send> TraceSink for JsonLinesTraceSink<W> {
+    fn record(&self, event: &TraceEvent) -> CLQResult<()> {
+        self.write_line(&event.to_json())
+    }
+}
@@ -7,18 +7,35 @@
 extern crate clap;
 extern crate serde_json;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::prelude::*;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 use clap::ArgMatches;
+use crossbeam::channel::{bounded, unbounded};
+use serde_json::{json, Map, Value};
 
-use crate::dachshund::beam::{Beam, BeamSearchResult};
+use crate::dachshund::annealing::{Annealer, AnnealingSchedule};
+use crate::dachshund::beam::{Beam, BeamSearchResult, SearchBudget, SeedingStrategy};
+use crate::dachshund::candidate::{Candidate, MoveSet};
+use crate::dachshund::drift::{CliqueDrift, DriftSink};
 use crate::dachshund::error::{CLQError, CLQResult};
+use crate::dachshund::exact::ExactSolver;
 use crate::dachshund::graph::{GraphBase, GraphBuilder};
+use crate::dachshund::hypergraph::{HyperGraph, HyperGraphBuilder};
 use crate::dachshund::id_types::{GraphId, EdgeTypeId, NodeId, NodeTypeId};
 use crate::dachshund::input::Input;
-use crate::dachshund::output::Output;
-use crate::dachshund::row::{CliqueRow, EdgeRow, Row};
+use crate::dachshund::input_spec::{InputSpec, LineNormalizer};
+use crate::dachshund::interner::{InternerScope, NodeIdInterner};
+use crate::dachshund::output::{json_f32, to_tsv_field, Output};
+use crate::dachshund::row::{CliqueRow, EdgeRow, HyperedgeRow, Row};
+use crate::dachshund::scorer::{
+    EdgeTypeConstraint, Scorer, ScorerType, SizeConstraints, SizeRange, TimeWindow,
+};
+use crate::dachshund::significance::{NullModel, Significance};
+use crate::dachshund::trace::{JsonLinesTraceSink, TraceSink};
 
 /// A mapping from opaque strings identifying node types (e.g. "author"), to the associated integer
 /// identifier used internally. Encapsulates some special/convenient accessor/mutator logic.
@@ -65,12 +82,89 @@ impl NonCoreTypeIds {
     }
 }
 
+/// The search algorithm used to find the top (quasi-)clique in each graph.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchAlgorithm {
+    /// heuristic beam search (see `Beam`).
+    Beam,
+    /// exhaustive branch-and-bound search, for small graphs (see `ExactSolver`).
+    Exact,
+    /// simulated annealing with restarts (see `Annealer`).
+    Annealing,
+}
+
+impl FromStr for SearchAlgorithm {
+    type Err = CLQError;
+    fn from_str(s: &str) -> CLQResult<Self> {
+        match s {
+            "beam" => Ok(SearchAlgorithm::Beam),
+            "exact" => Ok(SearchAlgorithm::Exact),
+            "annealing" => Ok(SearchAlgorithm::Annealing),
+            _ => Err(CLQError::from(format!("Unknown search algorithm: {}", s))),
+        }
+    }
+}
+
+/// The size of the graph a (quasi-)clique was found in, the time spent searching for it
+/// and the components of its score, printed along with its cliqueness, number of search
+/// steps and early stop reason (if any) if `Transformer::search_metadata` is set. The number
+/// of nodes and edges of the graph before pruning is only known when mining graphs from rows.
+struct SearchMetadata {
+    num_nodes_before_pruning: Option<usize>,
+    num_edges_before_pruning: Option<usize>,
+    num_nodes: usize,
+    num_edges: usize,
+    elapsed: Duration,
+    score_components: Vec<(&'static str, f32)>,
+}
+impl SearchMetadata {
+    fn new<TGraph: GraphBase>(
+        graph: &TGraph,
+        unpruned_size: Option<(usize, usize)>,
+        elapsed: Duration,
+        score_components: Vec<(&'static str, f32)>,
+    ) -> Self {
+        Self {
+            num_nodes_before_pruning: unpruned_size.map(|(num_nodes, _)| num_nodes),
+            num_edges_before_pruning: unpruned_size.map(|(_, num_edges)| num_edges),
+            num_nodes: graph.count_nodes(),
+            num_edges: graph.count_edges(),
+            elapsed,
+            score_components,
+        }
+    }
+    /// the number of nodes and edges of the (unpruned) graph built from `rows`, counted as
+    /// per `GraphBuilder::populate_edges`: edges between nodes of different types are
+    /// counted in both directions.
+    fn get_unpruned_size(rows: &[EdgeRow]) -> (usize, usize) {
+        let node_ids: HashSet<NodeId> = rows
+            .iter()
+            .flat_map(|r| vec![r.source_id, r.target_id])
+            .collect();
+        let num_edges: usize = rows
+            .iter()
+            .map(|r| match r.source_type_id == r.target_type_id {
+                true => 1,
+                false => 2,
+            })
+            .sum();
+        (node_ids.len(), num_edges)
+    }
+}
+
 /// Used to set up the typed graph clique mining algorithm. 
 pub struct Transformer {
     pub core_type: String,
     pub non_core_type_ids: NonCoreTypeIds,
     pub non_core_types: Vec<String>,
     pub edge_types: Vec<String>,
+    /// the distinct (edge type, non-core type) pairs of the typespec's relations from the
+    /// core type, in typespec order.
+    pub relations: Vec<(EdgeTypeId, NodeTypeId)>,
+    /// the (source type, target type) pairs of the typespec's relations between non-core
+    /// types, one per relation. If any, the scorer measures density over the typed subgraph
+    /// induced by each candidate, rather than over core -> non-core ties only.
+    pub non_core_relations: Vec<(NodeTypeId, NodeTypeId)>,
     pub beam_size: usize,
     pub alpha: f32,
     pub global_thresh: Option<f32>,
@@ -82,15 +176,101 @@ pub struct Transformer {
     pub debug: bool,
     pub min_degree: usize,
     pub long_format: bool,
+    /// maximum number of non-overlapping (quasi-)cliques to mine per graph (defaults to 1).
+    pub num_cliques: usize,
+    /// the objective maximized by the search (defaults to `ScorerType::Default`).
+    pub scorer_type: ScorerType,
+    /// minimum and maximum sizes of valid (quasi-)cliques (defaults to no constraints).
+    pub size_constraints: SizeConstraints,
+    /// minimum densities of ties of given edge types (defaults to no constraints).
+    pub edge_type_constraints: Vec<EdgeTypeConstraint>,
+    /// whether output rows end with the density of each relation in `self.relations`.
+    pub edge_type_densities: bool,
+    /// maximum time span of, and penalty on the time span of, the timestamped ties of
+    /// valid (quasi-)cliques (defaults to no constraints).
+    pub time_window: TimeWindow,
+    /// whether output rows end with the times of the first and last timestamped ties of
+    /// each (quasi-)clique.
+    pub time_span_columns: bool,
+    /// the search algorithm (defaults to `SearchAlgorithm::Beam`).
+    pub algorithm: SearchAlgorithm,
+    /// maximum number of candidates scored by the exact solver, per graph.
+    pub exact_node_budget: usize,
+    /// maximum amount of time spent by the exact solver, per graph.
+    pub exact_timeout: Duration,
+    /// temperature schedule and number of restarts of the annealing search, per graph.
+    pub annealing_schedule: AnnealingSchedule,
+    /// number of threads used to process graphs (defaults to 1, i.e. sequential processing).
+    pub num_threads: usize,
+    /// moves considered by the beam search (defaults to add moves only).
+    pub move_set: MoveSet,
+    /// how the beam is initialized (defaults to random walks of length 7).
+    pub seeding_strategy: SeedingStrategy,
+    /// if provided, combined with each graph_id to seed the beam search's pseudorandom
+    /// sequence.
+    pub rng_seed: Option<u64>,
+    /// if provided, receives structured events over the course of each beam (or annealing)
+    /// search.
+    pub tracer: Option<Box<dyn TraceSink>>,
+    /// per-graph limits on the resources spent by the beam (or annealing) search (defaults
+    /// to no limits).
+    /// If any limit is set, each output row records whether the search was truncated.
+    pub search_budget: SearchBudget,
+    /// if provided, receives, for each graph with seed cliques, how each of these compares
+    /// to the top candidate found by the search.
+    pub drift_sink: Option<Box<dyn DriftSink>>,
+    /// if provided, each printed (quasi-)clique is re-scored on graphs rewired as per the
+    /// null model, and its output rows end with an empirical p-value and a z-score.
+    pub null_model: Option<NullModel>,
+    /// whether output rows end with the core type, to tell apart the (quasi-)cliques of
+    /// different core types (see `MultiCoreTransformer`).
+    pub core_type_column: bool,
+    /// whether edge rows describe hyperedges, tying a core node to several non-core nodes
+    /// at once (see `process_line`). If so, graphs are built as `HyperGraph`s.
+    pub hypergraph: bool,
+    /// if provided, node ids are arbitrary strings (e.g. emails), interned into `NodeId`s,
+    /// and printed back as such.
+    pub node_id_interner: Option<NodeIdInterner>,
+    /// describes the layout of input lines (delimiter, header row and column positions),
+    /// which are rewritten into the layout described in `process_line` before being processed.
+    pub input_spec: InputSpec,
+    /// whether to print each (quasi-)clique as a JSON object, on its own line, rather than
+    /// as tab-separated wide or long format rows.
+    pub json_lines_output: bool,
+    /// whether output rows end with metadata about the search for each (quasi-)clique
+    /// (see `SearchMetadata`), to audit its quality.
+    pub search_metadata: bool,
 }
 impl Transformer {
+    /// the columns of input lines, as described in `process_line`, of which the first
+    /// `NUM_REQUIRED_INPUT_COLUMNS` are required. Clique rows use the same columns, with
+    /// node_id under core_id, node_type under non_core_id and clique_id under edge_type.
+    pub const INPUT_COLUMNS: &'static [&'static str] = &[
+        "graph_id",
+        "core_id",
+        "non_core_id",
+        "core_type",
+        "edge_type",
+        "non_core_type",
+        "weight",
+        "timestamp",
+    ];
+    pub const NUM_REQUIRED_INPUT_COLUMNS: usize = 6;
+    /// keys under which JSON objects describing clique rows may provide their columns.
+    pub const CLIQUE_ROW_INPUT_KEYS: &'static [(&'static str, &'static str)] = &[
+        ("core_id", "node_id"),
+        ("non_core_id", "node_type"),
+        ("edge_type", "clique_id"),
+    ];
+
     /// processes a "typespec", a command-line argument, of the form:
     /// [["author", "published_in", "journal"], ["author", "co-authored", "article"]].
     /// This sets up the semantics related to the set of relations contained in the
-    /// typed graph. A requirement is that all relations share a "core" type, in this
-    /// case, "author". Non-core types must be listed in a vector, which is used to
-    /// index the non core-types. The function creates a vector of NonCoreTypeIds, which
-    /// will then be used to process input rows.
+    /// typed graph. Relations either go from the "core" type, in this case, "author", to
+    /// a non-core type, or connect two non-core types (e.g. ["article", "published_in",
+    /// "journal"], alongside ["author", "wrote", "article"]). Non-core types must be
+    /// listed in a vector, which is used to index the non core-types. The function creates
+    /// a vector of NonCoreTypeIds, which will then be used to process input rows.
     pub fn process_typespec(
         typespec: Vec<Vec<String>>,
         core_type: &str,
@@ -99,19 +279,96 @@ impl Transformer {
         let mut non_core_type_ids = NonCoreTypeIds::new();
         non_core_type_ids.insert(core_type, NodeTypeId::from(0 as usize));
 
-        let should_be_only_this_core_type = &typespec[0][0].clone();
         for (non_core_type_ix, non_core_type) in non_core_types.iter().enumerate() {
             non_core_type_ids.insert(&non_core_type, NodeTypeId::from(non_core_type_ix + 1));
         }
         for item in typespec {
-            let core_type = &item[0];
+            let source_type = &item[0];
             let non_core_type = &item[2];
-            assert_eq!(core_type, should_be_only_this_core_type);
+            if source_type != core_type {
+                // a relation between non-core types, both of which must be declared.
+                if !non_core_types.contains(source_type) {
+                    return Err(CLQError::from(format!(
+                        "Relation source type {} is neither the core type nor a non-core type.",
+                        source_type
+                    )));
+                }
+                continue;
+            }
             let non_core_type_id: &mut NodeTypeId = non_core_type_ids.require_mut(non_core_type)?;
             non_core_type_id.increment_possible_edge_count();
         }
+        non_core_type_ids.require_mut(core_type)?.make_core();
         Ok(non_core_type_ids)
     }
+    /// processes size constraints, a command-line argument, of the form:
+    /// {"core": [3, null], "non_core": [2, 10], "ip": [2, null]}.
+    /// Each key is either "core", "non_core", or a non-core type, and maps to the minimum
+    /// and maximum (null if unbounded) number of such nodes in a valid (quasi-)clique.
+    pub fn process_size_constraints(
+        size_constraints: &str,
+        non_core_type_ids: &NonCoreTypeIds,
+    ) -> CLQResult<SizeConstraints> {
+        let ranges: HashMap<String, (usize, Option<usize>)> =
+            serde_json::from_str(size_constraints)?;
+        let mut constraints = SizeConstraints::default();
+        for (key, (min, max)) in ranges {
+            if let Some(max) = max {
+                if max < min {
+                    return Err(CLQError::from(format!(
+                        "Size constraint for {} has max < min.",
+                        key
+                    )));
+                }
+            }
+            let range = SizeRange::new(min, max);
+            match key.as_str() {
+                "core" => constraints.core = range,
+                "non_core" => constraints.non_core = range,
+                _ => {
+                    let non_core_type: NodeTypeId = *non_core_type_ids.require(&key)?;
+                    constraints.non_core_types.insert(non_core_type, range);
+                }
+            }
+        }
+        Ok(constraints)
+    }
+    /// processes edge type constraints, a command-line argument, of the form:
+    /// {"shares_ip": 1.0}. Each key is an edge type, and maps to the minimum density of
+    /// such ties between core nodes and the non-core nodes they may be tied to this way
+    /// (as per `relations`). A density of 1.0 requires each core node to have such a tie
+    /// to each such non-core node in the (quasi-)clique.
+    pub fn process_edge_type_constraints(
+        edge_type_constraints: &str,
+        edge_types: &[String],
+        relations: &[(EdgeTypeId, NodeTypeId)],
+    ) -> CLQResult<Vec<EdgeTypeConstraint>> {
+        let min_densities: BTreeMap<String, f32> = serde_json::from_str(edge_type_constraints)?;
+        let mut constraints: Vec<EdgeTypeConstraint> = Vec::new();
+        for (edge_type, min_density) in min_densities {
+            if !(0.0..=1.0).contains(&min_density) {
+                return Err(CLQError::from(format!(
+                    "Min density for edge type {} must be in [0, 1], got: {}",
+                    edge_type, min_density
+                )));
+            }
+            let edge_type_id: EdgeTypeId = edge_types
+                .iter()
+                .position(|r| *r == edge_type)
+                .ok_or_else(|| CLQError::from(format!("Unknown edge type: {}", edge_type)))?
+                .into();
+            for &(relation_edge_type, non_core_type) in relations {
+                if relation_edge_type == edge_type_id {
+                    constraints.push(EdgeTypeConstraint {
+                        edge_type: edge_type_id,
+                        non_core_type,
+                        min_density,
+                    });
+                }
+            }
+        }
+        Ok(constraints)
+    }
     /// Called by main.rs module to set up the beam search. Parameters are as follows:
     ///     - `typespec`: a command-line argument, of the form:
     ///     [["author", "published_in", "journal"], ["author", "co-authored", "article"]].
@@ -160,16 +417,42 @@ impl Transformer {
         non_core_types.sort();
 
         let num_non_core_types: usize = non_core_types.len();
+        let relation_names: Vec<(String, String, String)> = typespec
+            .iter()
+            .map(|x| (x[0].clone(), x[1].clone(), x[2].clone()))
+            .collect();
         let non_core_type_ids: NonCoreTypeIds = Transformer::process_typespec(
             typespec,
             &core_type,
             non_core_types.to_vec(),
         )?;
+        let mut relations: Vec<(EdgeTypeId, NodeTypeId)> = Vec::new();
+        let mut non_core_relations: Vec<(NodeTypeId, NodeTypeId)> = Vec::new();
+        for (source_type, edge_type, non_core_type) in relation_names {
+            if source_type != core_type {
+                non_core_relations.push((
+                    *non_core_type_ids.require(&source_type)?,
+                    *non_core_type_ids.require(&non_core_type)?,
+                ));
+                continue;
+            }
+            let edge_type_id: EdgeTypeId = edge_types
+                .iter()
+                .position(|r| *r == edge_type)
+                .ok_or_else(CLQError::err_none)?
+                .into();
+            let relation = (edge_type_id, *non_core_type_ids.require(&non_core_type)?);
+            if !relations.contains(&relation) {
+                relations.push(relation);
+            }
+        }
         let transformer = Self {
             core_type,
             non_core_type_ids,
             non_core_types,
             edge_types,
+            relations,
+            non_core_relations,
             beam_size,
             alpha,
             global_thresh,
@@ -181,19 +464,60 @@ impl Transformer {
             debug,
             min_degree,
             long_format,
+            num_cliques: 1,
+            scorer_type: ScorerType::Default,
+            size_constraints: SizeConstraints::default(),
+            edge_type_constraints: Vec::new(),
+            edge_type_densities: false,
+            time_window: TimeWindow::default(),
+            time_span_columns: false,
+            algorithm: SearchAlgorithm::Beam,
+            exact_node_budget: 1_000_000,
+            exact_timeout: Duration::from_secs(60),
+            annealing_schedule: AnnealingSchedule::default(),
+            num_threads: 1,
+            move_set: MoveSet::default(),
+            seeding_strategy: SeedingStrategy::default(),
+            rng_seed: None,
+            tracer: None,
+            drift_sink: None,
+            null_model: None,
+            core_type_column: false,
+            hypergraph: false,
+            node_id_interner: None,
+            input_spec: InputSpec::default(),
+            json_lines_output: false,
+            search_metadata: false,
+            search_budget: SearchBudget::default(),
         };
         Ok(transformer)
     }
 
     /// constructs a transformer from an ArgMatches object (to help with command line arguments).
     pub fn from_argmatches(matches: ArgMatches) -> CLQResult<Self> {
-        let arg_value = |name: &str| -> CLQResult<&str> {
-            matches
-                .value_of(name)
-                .ok_or_else(|| CLQError::from(format!("Missing required argument: {}", name)))
-        };
-        let typespec_str: &str = arg_value("typespec")?;
+        let typespec_str: &str = get_required_arg(&matches, "typespec")?;
         let typespec: Vec<Vec<String>> = serde_json::from_str(typespec_str)?;
+        let core_type: String = get_required_arg(&matches, "core_type")?.to_string();
+        let mut transformer =
+            Transformer::from_argmatches_with_types(&matches, typespec, core_type)?;
+        transformer.tracer = match matches.value_of("trace_file") {
+            Some(path) => Some(Box::new(JsonLinesTraceSink::from_file(path)?)),
+            None => None,
+        };
+        transformer.drift_sink = match matches.value_of("drift_file") {
+            Some(path) => Some(Box::new(JsonLinesTraceSink::from_file(path)?)),
+            None => None,
+        };
+        Ok(transformer)
+    }
+    /// Same as `from_argmatches`, but with the given typespec and core type, instead of
+    /// those provided on the command line. Trace and drift files are left to the caller.
+    pub fn from_argmatches_with_types(
+        matches: &ArgMatches,
+        typespec: Vec<Vec<String>>,
+        core_type: String,
+    ) -> CLQResult<Self> {
+        let arg_value = |name: &str| -> CLQResult<&str> { get_required_arg(matches, name) };
         let beam_size: usize = arg_value("beam_size")?.parse::<usize>()?;
         let alpha: f32 = arg_value("alpha")?.parse::<f32>()?;
         let global_thresh: Option<f32> = Some(arg_value("global_thresh")?.parse::<f32>()?);
@@ -204,9 +528,86 @@ impl Transformer {
             arg_value("max_repeated_prior_scores")?.parse::<usize>()?;
         let debug: bool = arg_value("debug_mode")?.parse::<bool>()?;
         let min_degree: usize = arg_value("min_degree")?.parse::<usize>()?;
-        let core_type: String = arg_value("core_type")?.parse::<String>()?;
         let long_format: bool = arg_value("long_format")?.parse::<bool>()?;
-        let transformer = Transformer::new(
+        let num_cliques: usize = match matches.value_of("num_cliques") {
+            Some(n) => n.parse::<usize>()?,
+            None => 1,
+        };
+        let scorer_type: ScorerType = match matches.value_of("scorer") {
+            Some(n) => n.parse::<ScorerType>()?,
+            None => ScorerType::Default,
+        };
+        let algorithm: SearchAlgorithm = match matches.value_of("algorithm") {
+            Some(n) => n.parse::<SearchAlgorithm>()?,
+            None => SearchAlgorithm::Beam,
+        };
+        let exact_node_budget: usize = match matches.value_of("exact_node_budget") {
+            Some(n) => n.parse::<usize>()?,
+            None => 1_000_000,
+        };
+        let exact_timeout: Duration = match matches.value_of("exact_timeout") {
+            Some(n) => Duration::from_secs_f64(n.parse::<f64>()?),
+            None => Duration::from_secs(60),
+        };
+        let mut annealing_schedule = AnnealingSchedule::default();
+        if let Some(n) = matches.value_of("annealing_temperature") {
+            annealing_schedule.initial_temperature = n.parse::<f32>()?;
+        }
+        if let Some(n) = matches.value_of("annealing_cooling") {
+            annealing_schedule.cooling = n.parse()?;
+        }
+        if let Some(n) = matches.value_of("annealing_steps") {
+            annealing_schedule.num_steps = n.parse::<usize>()?;
+        }
+        if let Some(n) = matches.value_of("annealing_restarts") {
+            annealing_schedule.num_restarts = n.parse::<usize>()?;
+        }
+        let num_threads: usize = match matches.value_of("num_threads") {
+            Some(n) => n.parse::<usize>()?,
+            None => 1,
+        };
+        let move_set: MoveSet = match matches.value_of("moves") {
+            Some(n) => n.parse::<MoveSet>()?,
+            None => MoveSet::default(),
+        };
+        let seeding_strategy: SeedingStrategy =
+            match (matches.value_of("seeding"), matches.value_of("seed_file")) {
+                (Some(_), Some(_)) => {
+                    return Err("Only one of --seeding and --seed_file can be provided.".into())
+                }
+                (Some(n), None) => n.parse::<SeedingStrategy>()?,
+                (None, Some(path)) => SeedingStrategy::from_file(path)?,
+                (None, None) => SeedingStrategy::default(),
+            };
+        let rng_seed: Option<u64> = match matches.value_of("rng_seed") {
+            Some(n) => Some(n.parse::<u64>()?),
+            None => None,
+        };
+        let null_model: Option<NullModel> = match matches.value_of("null_samples") {
+            Some(n) => {
+                let num_samples: usize = n.parse::<usize>()?;
+                if num_samples == 0 {
+                    return Err("The null model requires at least one sample.".into());
+                }
+                let mut null_model = NullModel::new(num_samples);
+                if let Some(m) = matches.value_of("null_swaps_per_edge") {
+                    null_model.swaps_per_edge = m.parse::<usize>()?;
+                }
+                Some(null_model)
+            }
+            None => None,
+        };
+        let search_budget = SearchBudget {
+            time: match matches.value_of("time_budget") {
+                Some(n) => Some(Duration::from_secs_f64(n.parse::<f64>()?)),
+                None => None,
+            },
+            num_expansions: match matches.value_of("expansion_budget") {
+                Some(n) => Some(n.parse::<usize>()?),
+                None => None,
+            },
+        };
+        let mut transformer = Transformer::new(
             typespec,
             beam_size,
             alpha,
@@ -220,6 +621,70 @@ impl Transformer {
             core_type,
             long_format,
         )?;
+        transformer.num_cliques = num_cliques;
+        transformer.scorer_type = scorer_type;
+        if let Some(n) = matches.value_of("size_constraints") {
+            transformer.size_constraints =
+                Transformer::process_size_constraints(n, &transformer.non_core_type_ids)?;
+        }
+        if let Some(n) = matches.value_of("edge_type_constraints") {
+            transformer.edge_type_constraints = Transformer::process_edge_type_constraints(
+                n,
+                &transformer.edge_types,
+                &transformer.relations,
+            )?;
+        }
+        transformer.edge_type_densities = matches.is_present("edge_type_densities");
+        let max_time_span: Option<i64> = match matches.value_of("max_time_span") {
+            Some(n) => Some(n.parse::<i64>()?),
+            None => None,
+        };
+        let time_span_penalty: f32 = match matches.value_of("time_span_penalty") {
+            Some(n) => n.parse::<f32>()?,
+            None => 0.0,
+        };
+        transformer.time_window = TimeWindow::new(max_time_span, time_span_penalty)?;
+        transformer.time_span_columns = matches.is_present("time_span_columns");
+        transformer.algorithm = algorithm;
+        transformer.exact_node_budget = exact_node_budget;
+        transformer.exact_timeout = exact_timeout;
+        transformer.annealing_schedule = annealing_schedule;
+        transformer.num_threads = num_threads;
+        transformer.move_set = move_set;
+        transformer.seeding_strategy = seeding_strategy;
+        transformer.rng_seed = rng_seed;
+        transformer.search_budget = search_budget;
+        transformer.null_model = null_model;
+        transformer.hypergraph = matches.is_present("hypergraph");
+        if let Some(n) = matches.value_of("intern_node_ids") {
+            transformer.node_id_interner = Some(NodeIdInterner::new(n.parse::<InternerScope>()?));
+        }
+        // seed files are read before any node id is interned.
+        if transformer.node_id_interner.is_some()
+            && matches!(transformer.seeding_strategy, SeedingStrategy::Supplied(_))
+        {
+            return Err("Seed files are not supported with interned node ids.".into());
+        }
+        if let Some(n) = matches.value_of("input_spec") {
+            transformer.input_spec = InputSpec::from_json(n)?;
+        }
+        transformer.json_lines_output = matches.is_present("json_lines_output");
+        transformer.search_metadata = matches.is_present("search_metadata");
+        if transformer.json_lines_output && transformer.long_format {
+            return Err("JSON Lines output is not supported in long format.".into());
+        }
+        if transformer.hypergraph && transformer.null_model.is_some() {
+            return Err("Null models are not supported for hypergraphs.".into());
+        }
+        if transformer.hypergraph && transformer.num_threads > 1 {
+            return Err("Hypergraphs are processed sequentially (num_threads must be 1).".into());
+        }
+        // the null model only rewires (and only recovers) ties from core nodes.
+        if !transformer.non_core_relations.is_empty() && transformer.null_model.is_some() {
+            return Err(
+                "Null models are not supported with relations between non-core types.".into(),
+            );
+        }
         Ok(transformer)
     }
 
@@ -236,47 +701,74 @@ impl Transformer {
     }
 
     /// processes a line of (tab-separated) input, of the form:
-    /// graph_id\tcore_id\tnon_core_id\tcore_type\tedge_type\tnon_core_type
+    /// graph_id\tcore_id\tnon_core_id\tcore_type\tedge_type\tnon_core_type[\tweight[\ttimestamp]]
     ///
     /// or:
     ///
-    /// graph_id\tnode_id\tnode_type
+    /// graph_id\tnode_id\tnode_type\t\t[clique_id]\t
+    ///
+    /// or, if `self.hypergraph` is set, the first row type is replaced by:
+    ///
+    /// graph_id\tcore_id\tnon_core_ids\tcore_type\tedge_type\tnon_core_types[\tweight]
     ///
-    /// Note that core_type is not used in the first row type. The second
-    /// row type is used to initialize the beam search with a single existing
-    /// clique, the best identified by some other search process. This existing
-    /// clique may be invalidated if it no longer meets cliqueness requirements
-    /// as per the current search process.
+    /// where non_core_ids is a comma-separated list of the non-core nodes tied to the core
+    /// node by a single hyperedge (e.g. one event), and non_core_types is either the
+    /// comma-separated list of their types, or a single type shared by all of them.
+    ///
+    /// Note that core_type must either be the core type, or the source type of a relation
+    /// between non-core types (in which case core_id is a non-core node). Ties between two
+    /// non-core nodes of the same type must be listed in both directions. The optional
+    /// weight column must be in (0, 1] (edges are unweighted, i.e. have weight 1.0, if it is
+    /// omitted or empty). The optional timestamp column is an integer, in any unit.
+    /// The second row type is used to initialize the beam search with existing
+    /// cliques, e.g. identified by some other search process. Rows sharing a clique_id
+    /// make up one seed clique (rows without a clique_id make up a single seed clique).
+    /// These existing cliques may be invalidated if they no longer meet cliqueness
+    /// requirements as per the current search process. Node ids must be integers, unless
+    /// `self.node_id_interner` is set (in which case they can be any string without tabs,
+    /// or commas in hyperedge rows).
     pub fn process_line(&self, line: String) -> CLQResult<Box<dyn Row>> {
         let vec: Vec<&str> = line.split('\t').collect();
+        if vec.len() < 6 || vec.len() > 8 {
+            return Err(CLQError::from(format!(
+                "Expected 6 to 8 tab-separated columns, got {} in line: {}",
+                vec.len(),
+                line
+            )));
+        }
         // this is an edge row if we have something on column 3
-        assert!(vec.len() == 6);
         let is_edge_row: bool = !vec[3].is_empty();
+        if is_edge_row && self.hypergraph {
+            return self.process_hyperedge_line(&vec);
+        }
         if is_edge_row {
             let graph_id: GraphId = vec[0].parse::<i64>()?.into();
-            let core_id: NodeId = vec[1].parse::<i64>()?.into();
-            let non_core_id: NodeId = vec[2].parse::<i64>()?.into();
+            let source_id: NodeId = self.parse_node_id(graph_id, vec[1])?;
+            let non_core_id: NodeId = self.parse_node_id(graph_id, vec[2])?;
+            let source_type: &str = vec[3].trim_end();
             let edge_type: &str = vec[4].trim_end();
             let non_core_type: &str = vec[5].trim_end();
             let non_core_type_id: NodeTypeId = *self.non_core_type_ids.require(non_core_type)?;
-            let edge_type_id: EdgeTypeId = self
-                .edge_types
-                .iter()
-                .position(|r| r == edge_type)
-                .ok_or_else(CLQError::err_none)?
-                .into();
-            let core_type_id: NodeTypeId = *self.non_core_type_ids.require(&self.core_type)?;
+            let edge_type_id: EdgeTypeId = self.get_edge_type_id(edge_type)?;
+            let source_type_id: NodeTypeId = self.get_source_type_id(source_type)?;
+            let weight: f32 = Transformer::parse_weight(vec.get(6))?;
+            let timestamp: Option<i64> = match vec.get(7).map(|x| x.trim_end()) {
+                Some(t) if !t.is_empty() => Some(t.parse::<i64>()?),
+                _ => None,
+            };
             return Ok(Box::new(EdgeRow {
                 graph_id,
-                source_id: core_id,
+                source_id,
                 target_id: non_core_id,
-                source_type_id: core_type_id,
+                source_type_id,
                 target_type_id: non_core_type_id,
                 edge_type_id,
+                weight,
+                timestamp,
             }));
         }
         let graph_id: GraphId = vec[0].parse::<i64>()?.into();
-        let node_id: NodeId = vec[1].parse::<i64>()?.into();
+        let node_id: NodeId = self.parse_node_id(graph_id, vec[1])?;
         let node_type: &str = vec[2].trim_end();
         let non_core_type: Option<NodeTypeId>;
         if node_type == self.core_type {
@@ -285,13 +777,107 @@ impl Transformer {
             let non_core_type_id: NodeTypeId = *self.non_core_type_ids.require(node_type)?;
             non_core_type = Some(non_core_type_id);
         }
+        let clique_id: Option<usize> = match vec[4].trim_end() {
+            "" => None,
+            n => Some(n.parse::<usize>()?),
+        };
         Ok(Box::new(CliqueRow {
             graph_id,
             node_id,
             target_type: non_core_type,
+            clique_id,
         }))
     }
-    /// Given a properly-built graph, runs the quasi-clique detection beam search on it.
+    /// returns the type id of the source of an edge row, which must be the core type, or
+    /// the source type of a relation between non-core types (in which case the source is a
+    /// non-core node).
+    fn get_source_type_id(&self, source_type: &str) -> CLQResult<NodeTypeId> {
+        if source_type == self.core_type {
+            return Ok(*self.non_core_type_ids.require(&self.core_type)?);
+        }
+        match self.non_core_type_ids.require(source_type) {
+            Ok(&type_id) if self.non_core_relations.iter().any(|&(s, _)| s == type_id) => {
+                Ok(type_id)
+            }
+            _ => Err(CLQError::from(format!(
+                "Invalid source type: {} (expected {}, or the source type of a relation \
+                 between non-core types).",
+                source_type, self.core_type
+            ))),
+        }
+    }
+    /// processes a (tab-separated) hyperedge row, as described in `process_line`.
+    fn process_hyperedge_line(&self, vec: &[&str]) -> CLQResult<Box<dyn Row>> {
+        let graph_id: GraphId = vec[0].parse::<i64>()?.into();
+        let core_id: NodeId = self.parse_node_id(graph_id, vec[1])?;
+        let non_core_ids: Vec<NodeId> = vec[2]
+            .split(',')
+            .map(|x| self.parse_node_id(graph_id, x.trim()))
+            .collect::<CLQResult<Vec<NodeId>>>()?;
+        let edge_type_id: EdgeTypeId = self.get_edge_type_id(vec[4].trim_end())?;
+        let mut non_core_type_ids: Vec<NodeTypeId> = vec[5]
+            .trim_end()
+            .split(',')
+            .map(|x| Ok(*self.non_core_type_ids.require(x.trim())?))
+            .collect::<CLQResult<Vec<NodeTypeId>>>()?;
+        // a single non-core type applies to all non-core nodes.
+        if non_core_type_ids.len() == 1 {
+            non_core_type_ids = vec![non_core_type_ids[0]; non_core_ids.len()];
+        }
+        if non_core_type_ids.len() != non_core_ids.len() {
+            return Err(CLQError::from(format!(
+                "Hyperedge has {} non-core nodes, but {} types.",
+                non_core_ids.len(),
+                non_core_type_ids.len()
+            )));
+        }
+        let weight: f32 = Transformer::parse_weight(vec.get(6))?;
+        if matches!(vec.get(7).map(|x| x.trim_end()), Some(t) if !t.is_empty()) {
+            return Err("Timestamps are not supported for hyperedges.".into());
+        }
+        Ok(Box::new(HyperedgeRow {
+            graph_id,
+            core_id,
+            non_core_ids,
+            non_core_type_ids,
+            edge_type_id,
+            weight,
+        }))
+    }
+    /// parses a node id, which is interned if `self.node_id_interner` is set, and must be
+    /// an integer otherwise.
+    fn parse_node_id(&self, graph_id: GraphId, s: &str) -> CLQResult<NodeId> {
+        match &self.node_id_interner {
+            Some(interner) => Ok(interner.intern(graph_id, s)),
+            None => Ok(s.parse::<i64>()?.into()),
+        }
+    }
+    fn get_edge_type_id(&self, edge_type: &str) -> CLQResult<EdgeTypeId> {
+        Ok(self
+            .edge_types
+            .iter()
+            .position(|r| r == edge_type)
+            .ok_or_else(CLQError::err_none)?
+            .into())
+    }
+    /// parses the optional weight column of an edge (or hyperedge) row.
+    fn parse_weight(column: Option<&&str>) -> CLQResult<f32> {
+        let weight: f32 = match column.map(|x| x.trim_end()) {
+            Some(w) if !w.is_empty() => w.parse::<f32>()?,
+            _ => 1.0,
+        };
+        if !(weight > 0.0 && weight <= 1.0) {
+            return Err(CLQError::from(format!(
+                "Edge weight must be in (0, 1], got: {}",
+                weight
+            )));
+        }
+        Ok(weight)
+    }
+    /// Given a properly-built graph, runs the quasi-clique detection search on it, using
+    /// the algorithm specified by `self.algorithm`. Seed cliques are ignored by the exact
+    /// solver, which explores the full search space anyway. The annealing search starts its
+    /// first runs from them.
     pub fn process_graph<'a, TGraph: GraphBase>(
         &'a self,
         graph: &'a TGraph,
@@ -299,18 +885,59 @@ impl Transformer {
         graph_id: GraphId,
         verbose: bool,
     ) -> CLQResult<BeamSearchResult<'a, TGraph>> {
+        self.search_graph(graph, clique_rows, graph_id, verbose, self.build_scorer())
+    }
+    /// Same as `process_graph`, but with the given scorer, which the caller may share
+    /// (see `process_ranked_clique_rows`).
+    fn search_graph<'a, TGraph: GraphBase>(
+        &'a self,
+        graph: &'a TGraph,
+        clique_rows: Vec<CliqueRow>,
+        graph_id: GraphId,
+        verbose: bool,
+        scorer: Box<dyn Scorer<TGraph> + 'a>,
+    ) -> CLQResult<BeamSearchResult<'a, TGraph>> {
+        if self.algorithm == SearchAlgorithm::Exact {
+            let mut solver: ExactSolver<TGraph> =
+                ExactSolver::new(graph, scorer, self.exact_node_budget, self.exact_timeout);
+            let result = solver.run_search()?;
+            if verbose && !solver.exhausted_search() {
+                eprintln!(
+                    "Exact search ran out of budget for graph {}, after {} steps.",
+                    graph_id.value(),
+                    result.num_steps
+                );
+            }
+            return Ok(result);
+        }
+        if self.algorithm == SearchAlgorithm::Annealing {
+            let mut annealer: Annealer<TGraph> = Annealer::new(
+                graph,
+                clique_rows,
+                scorer,
+                &self.non_core_types,
+                graph_id,
+                self.rng_seed,
+            )?;
+            annealer.tracer = self.tracer.as_deref();
+            annealer.verbose = verbose;
+            annealer.budget = self.search_budget;
+            return annealer.run_search(&self.annealing_schedule);
+        }
         let mut beam: Beam<TGraph> = Beam::new(
             graph,
             clique_rows,
             self.beam_size,
             verbose,
             &self.non_core_types,
-            self.num_non_core_types,
-            self.alpha,
-            self.global_thresh,
-            self.local_thresh,
+            scorer,
             graph_id,
+            &self.seeding_strategy,
+            self.rng_seed,
         )?;
+        beam.move_set = self.move_set;
+        beam.tracer = self.tracer.as_deref();
+        beam.budget = self.search_budget;
         beam.run_search(
             self.num_to_search,
             self.beam_size,
@@ -318,6 +945,21 @@ impl Transformer {
             self.max_repeated_prior_scores,
         )
     }
+    /// builds the scorer specified by `self.scorer_type`, with the thresholds provided in
+    /// the constructor, enforcing `self.size_constraints`, `self.edge_type_constraints` and
+    /// `self.time_window` (if any), and accounting for `self.non_core_relations`.
+    fn build_scorer<TGraph: GraphBase>(&self) -> Box<dyn Scorer<TGraph>> {
+        self.scorer_type.build_with_constraints(
+            self.num_non_core_types,
+            self.alpha,
+            self.global_thresh,
+            self.local_thresh,
+            self.size_constraints.clone(),
+            self.edge_type_constraints.clone(),
+            self.non_core_relations.clone(),
+            self.time_window,
+        )
+    }
     /// Used to "seed" the beam search with an existing best (quasi-)clique (if any provided),
     /// and then run the search under the parameters specified in the constructor. 
     pub fn process_clique_rows<'a, TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
@@ -327,89 +969,630 @@ impl Transformer {
         graph_id: GraphId,
         verbose: bool,
         output: &mut Output,
+    ) -> CLQResult<Option<BeamSearchResult<'a, TGraph>>> {
+        self.process_ranked_clique_rows::<TGraphBuilder, TGraph>(
+            graph,
+            clique_rows,
+            graph_id,
+            None,
+            None,
+            verbose,
+            output,
+        )
+    }
+    /// Same as `process_clique_rows`, but tags the printed (quasi-)clique with its rank,
+    /// if one is provided, and with the number of nodes and edges of the graph before it
+    /// was pruned, if provided (see `self.search_metadata`). If `self.drift_sink` is set,
+    /// also records how each seed clique compares to the top candidate.
+    #[allow(clippy::too_many_arguments)]
+    fn process_ranked_clique_rows<'a, TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+        &'a self,
+        graph: &'a TGraph,
+        clique_rows: Vec<CliqueRow>,
+        graph_id: GraphId,
+        clique_index: Option<usize>,
+        unpruned_size: Option<(usize, usize)>,
+        verbose: bool,
+        output: &mut Output,
     ) -> CLQResult<Option<BeamSearchResult<'a, TGraph>>> {
         if graph.get_core_ids().is_empty() || graph.get_non_core_ids().unwrap().is_empty() {
             return Ok(None);
         }
+        let prior_rows: Vec<CliqueRow> = match self.drift_sink {
+            Some(_) => clique_rows.clone(),
+            None => Vec::new(),
+        };
+        let scorer: Rc<dyn Scorer<TGraph>> = Rc::from(self.build_scorer());
+        let start: Instant = Instant::now();
         let result: BeamSearchResult<TGraph> =
-            self.process_graph(graph, clique_rows, graph_id, verbose)?;
+            self.search_graph(graph, clique_rows, graph_id, verbose, Box::new(scorer.clone()))?;
+        let elapsed: Duration = start.elapsed();
+        let metadata = SearchMetadata::new(
+            graph,
+            unpruned_size,
+            elapsed,
+            self.get_score_components(&result.top_candidate, scorer.as_ref())?,
+        );
+        self.record_drift(&prior_rows, graph, &result.top_candidate, scorer.as_ref())?;
         // only print if this is a conforming clique
         if result.top_candidate.get_score()? > 0.0 {
-            if !self.long_format {
-                let line: String = format!(
-                    "{}\t{}",
-                    graph_id.value(),
-                    result.top_candidate.to_printable_row(&self.non_core_types)?,
-                );
-                output.print(line)?;
-            } else {
-                result.top_candidate.print(
+            let significance: Option<Significance> = match self.null_model {
+                Some(_) => Some(self.measure_significance::<TGraphBuilder, TGraph>(
+                    graph,
+                    &result.top_candidate,
                     graph_id,
-                    &self.non_core_types,
-                    &self.core_type,
+                    scorer.as_ref(),
+                )?),
+                None => None,
+            };
+            self.print_candidate(
+                &result,
+                graph_id,
+                clique_index,
+                significance.as_ref(),
+                &metadata,
+                output,
+            )?;
+        }
+        Ok(Some(result))
+    }
+    /// if `self.drift_sink` is set, records how each seed clique in `prior_rows` compares to
+    /// the top candidate found by the search.
+    fn record_drift<TGraph: GraphBase>(
+        &self,
+        prior_rows: &[CliqueRow],
+        graph: &TGraph,
+        top_candidate: &Candidate<TGraph>,
+        scorer: &dyn Scorer<TGraph>,
+    ) -> CLQResult<()> {
+        if let Some(drift_sink) = &self.drift_sink {
+            for drift in CliqueDrift::from_clique_rows(prior_rows, graph, top_candidate, scorer)? {
+                drift_sink.record(&drift, self.node_id_interner.as_ref())?;
+            }
+        }
+        Ok(())
+    }
+    /// the components of the score of `candidate`, as printed if `self.search_metadata` is
+    /// set (and not computed otherwise).
+    fn get_score_components<TGraph: GraphBase>(
+        &self,
+        candidate: &Candidate<TGraph>,
+        scorer: &dyn Scorer<TGraph>,
+    ) -> CLQResult<Vec<(&'static str, f32)>> {
+        match self.search_metadata {
+            true => scorer.get_score_components(candidate),
+            false => Ok(Vec::new()),
+        }
+    }
+    /// the optional columns printed after a (quasi-)clique: whether the search was truncated
+    /// (if a search budget is set), its significance (if provided), the density of each
+    /// relation (if `self.edge_type_densities` is set), the times of its first and last
+    /// timestamped ties (if `self.time_span_columns` is set, empty if there are none),
+    /// metadata about the search (if `self.search_metadata` is set, see `SearchMetadata`),
+    /// and the core type (if `self.core_type_column` is set). Columns are named, so that they
+    /// can be printed as fields of JSON Lines output, and null values are printed as empty
+    /// columns.
+    fn get_extra_columns<TGraph: GraphBase>(
+        &self,
+        result: &BeamSearchResult<TGraph>,
+        significance: Option<&Significance>,
+        metadata: &SearchMetadata,
+    ) -> CLQResult<Vec<(&'static str, Value)>> {
+        let mut extra_columns: Vec<(&'static str, Value)> = Vec::new();
+        if self.search_budget.is_set() {
+            extra_columns.push(("truncated", Value::Bool(result.truncated)));
+        }
+        if let Some(significance) = significance {
+            extra_columns.push(("p_value", json_f32(significance.p_value)));
+            extra_columns.push(("z_score", significance.z_score.map_or(Value::Null, json_f32)));
+        }
+        if self.edge_type_densities {
+            let densities: Vec<Option<f32>> = result
+                .top_candidate
+                .get_edge_type_densities(&self.relations)?;
+            extra_columns.push((
+                "edge_type_densities",
+                densities
+                    .into_iter()
+                    .map(|density| density.map_or(Value::Null, json_f32))
+                    .collect(),
+            ));
+        }
+        if self.time_span_columns {
+            let time_span: Option<(i64, i64)> = result.top_candidate.get_time_span();
+            extra_columns.push(("first_timestamp", json!(time_span.map(|(first, _)| first))));
+            extra_columns.push(("last_timestamp", json!(time_span.map(|(_, last)| last))));
+        }
+        if self.search_metadata {
+            let score_components: Map<String, Value> = metadata
+                .score_components
+                .iter()
+                .map(|&(name, value)| (name.to_string(), json_f32(value)))
+                .collect();
+            extra_columns.extend(vec![
+                ("score_components", Value::Object(score_components)),
+                ("cliqueness", json_f32(result.top_candidate.get_cliqueness()?)),
+                ("num_steps", json!(result.num_steps)),
+                ("stop_reason", json!(result.stop_reason)),
+                ("num_nodes_before_pruning", json!(metadata.num_nodes_before_pruning)),
+                ("num_edges_before_pruning", json!(metadata.num_edges_before_pruning)),
+                ("num_nodes_after_pruning", json!(metadata.num_nodes)),
+                ("num_edges_after_pruning", json!(metadata.num_edges)),
+                ("elapsed_ms", json!(metadata.elapsed.as_millis() as u64)),
+            ]);
+        }
+        if self.core_type_column {
+            extra_columns.push(("core_type", Value::String(self.core_type.clone())));
+        }
+        Ok(extra_columns)
+    }
+    /// measures how surprising the score of `candidate` is under `self.null_model`, which
+    /// rewires the edges of `graph` (see `NullModel`).
+    pub fn get_significance<TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+        &self,
+        graph: &TGraph,
+        candidate: &Candidate<TGraph>,
+        graph_id: GraphId,
+    ) -> CLQResult<Significance> {
+        let scorer = self.build_scorer();
+        self.measure_significance::<TGraphBuilder, TGraph>(
+            graph,
+            candidate,
+            graph_id,
+            scorer.as_ref(),
+        )
+    }
+    /// Same as `get_significance`, but with the given scorer.
+    fn measure_significance<TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+        &self,
+        graph: &TGraph,
+        candidate: &Candidate<TGraph>,
+        graph_id: GraphId,
+        scorer: &dyn Scorer<TGraph>,
+    ) -> CLQResult<Significance> {
+        let null_model: &NullModel = self.null_model.as_ref().ok_or_else(CLQError::err_none)?;
+        let core_type_id: NodeTypeId = *self.non_core_type_ids.require(&self.core_type)?;
+        let rows: Vec<EdgeRow> = NullModel::get_edge_rows(graph, graph_id, core_type_id)?;
+        null_model.get_significance::<TGraphBuilder, TGraph>(
+            candidate,
+            &rows,
+            graph_id,
+            scorer,
+            self.rng_seed,
+        )
+    }
+    /// prints the top candidate of a search in wide or long format, as per
+    /// `self.long_format`, or as a JSON object, if `self.json_lines_output` is set. If a
+    /// `clique_index` is provided, it is printed right after the graph_id. Any extra
+    /// columns (see `get_extra_columns`) are printed last. Interned node ids are printed
+    /// as their original strings.
+    fn print_candidate<TGraph: GraphBase>(
+        &self,
+        result: &BeamSearchResult<TGraph>,
+        graph_id: GraphId,
+        clique_index: Option<usize>,
+        significance: Option<&Significance>,
+        metadata: &SearchMetadata,
+        output: &mut Output,
+    ) -> CLQResult<()> {
+        let candidate: &Candidate<TGraph> = &result.top_candidate;
+        let named_extra_columns: Vec<(&'static str, Value)> =
+            self.get_extra_columns(result, significance, metadata)?;
+        if self.json_lines_output {
+            let interner = self
+                .node_id_interner
+                .as_ref()
+                .map(|interner| (interner, graph_id));
+            let mut object: Map<String, Value> =
+                candidate.to_json_with_interner(&self.non_core_types, interner)?;
+            object.insert("graph_id".into(), json!(graph_id.value()));
+            if let Some(ix) = clique_index {
+                object.insert("clique_index".into(), json!(ix));
+            }
+            object.insert("score".into(), json_f32(candidate.get_score()?));
+            object.insert("num_steps".into(), json!(result.num_steps));
+            for (name, value) in named_extra_columns {
+                object.insert(name.to_string(), value);
+            }
+            return output.print(Value::Object(object).to_string());
+        }
+        let extra_columns: Vec<String> = named_extra_columns
+            .iter()
+            .map(|(_, value)| to_tsv_field(value))
+            .collect();
+        if !self.long_format {
+            let mut line: String = graph_id.value().to_string();
+            if let Some(ix) = clique_index {
+                line.push_str(&format!("\t{}", ix));
+            }
+            let interner = self
+                .node_id_interner
+                .as_ref()
+                .map(|interner| (interner, graph_id));
+            line.push_str(&format!(
+                "\t{}",
+                candidate.to_printable_row_with_interner(&self.non_core_types, interner)?
+            ));
+            for column in &extra_columns {
+                line.push('\t');
+                line.push_str(column);
+            }
+            output.print(line)?;
+        } else {
+            candidate.print_with_interner(
+                graph_id,
+                clique_index,
+                &extra_columns,
+                &self.non_core_types,
+                &self.core_type,
+                self.node_id_interner.as_ref(),
+                output,
+            )?;
+        }
+        Ok(())
+    }
+    /// Mines up to `self.num_cliques` non-overlapping (quasi-)cliques from the graph described
+    /// by `rows`. After each search, the nodes belonging to the top candidate are removed from
+    /// the graph, which is then rebuilt (and re-pruned) before searching again. Stops early
+    /// if the graph runs out of nodes, or if the top candidate's score is no longer positive.
+    /// Seed cliques (if any) are only used for the first search. If more than one clique is
+    /// requested, each printed clique is tagged with its rank. Returns the output rows of
+    /// each conforming clique found, in rank order.
+    #[allow(clippy::ptr_arg)]
+    pub fn process_top_k_cliques<TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+        &self,
+        rows: &Vec<EdgeRow>,
+        clique_rows: Vec<CliqueRow>,
+        graph_id: GraphId,
+        verbose: bool,
+        output: &mut Output,
+    ) -> CLQResult<Vec<Vec<CliqueRow>>> {
+        let mut cliques: Vec<Vec<CliqueRow>> = Vec::new();
+        let mut remaining_rows: Vec<EdgeRow> = rows.clone();
+        let mut seed_rows: Vec<CliqueRow> = clique_rows;
+        for clique_index in 0..self.num_cliques {
+            let unpruned_size: Option<(usize, usize)> = match self.search_metadata {
+                true => Some(SearchMetadata::get_unpruned_size(&remaining_rows)),
+                false => None,
+            };
+            let graph: TGraph =
+                self.build_pruned_graph::<TGraphBuilder, TGraph>(graph_id, &remaining_rows)?;
+            let rank: Option<usize> = match self.num_cliques {
+                1 => None,
+                _ => Some(clique_index),
+            };
+            let result: Option<BeamSearchResult<TGraph>> = self
+                .process_ranked_clique_rows::<TGraphBuilder, TGraph>(
+                    &graph,
+                    std::mem::take(&mut seed_rows),
+                    graph_id,
+                    rank,
+                    unpruned_size,
+                    verbose,
                     output,
                 )?;
+            let top_candidate: Candidate<TGraph> = match result {
+                Some(r) => r.top_candidate,
+                None => break,
+            };
+            if top_candidate.get_score()? <= 0.0 {
+                break;
             }
+            let found: HashSet<NodeId> = top_candidate
+                .core_ids
+                .union(&top_candidate.non_core_ids)
+                .cloned()
+                .collect();
+            cliques.push(top_candidate.get_output_rows(graph_id)?);
+            remaining_rows
+                .retain(|r| !(found.contains(&r.source_id) || found.contains(&r.target_id)));
         }
-        Ok(Some(result))
+        self.release_node_ids(graph_id);
+        Ok(cliques)
     }
-    /// to be called by main.rs (or a test), using an input (such as stdin), 
-    /// which must provide a lines() function, and an output (such as stdout), to
-    /// which it is possible to write line-by-line. Typical reducer logic: 
-    /// read one line at a time, with the first column being the key. If key has not
-    /// changed, keep accumulating lines. If key has changed, process accumulated
-    /// lines, output results and reset state.  
-    pub fn run<TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+    /// Same as `process_top_k_cliques`, but for the hypergraph described by hyperedge `rows`.
+    /// After each search, the core nodes belonging to the top candidate are removed along
+    /// with their hyperedges, and its non-core nodes are removed from the remaining
+    /// hyperedges (which are dropped once empty). Hypergraphs are not pruned by degree.
+    pub fn process_top_k_hyperedge_cliques(
         &self,
-        input: Input,
+        rows: &[HyperedgeRow],
+        clique_rows: Vec<CliqueRow>,
+        graph_id: GraphId,
+        verbose: bool,
         output: &mut Output,
-    ) -> CLQResult<()> {
-        let mut edge_rows: Vec<EdgeRow> = Vec::new();
-        let mut clique_rows: Vec<CliqueRow> = Vec::new();
+    ) -> CLQResult<Vec<Vec<CliqueRow>>> {
+        let mut cliques: Vec<Vec<CliqueRow>> = Vec::new();
+        let mut remaining_rows: Vec<HyperedgeRow> = rows.to_vec();
+        let mut seed_rows: Vec<CliqueRow> = clique_rows;
+        for clique_index in 0..self.num_cliques {
+            let graph: HyperGraph = HyperGraphBuilder::from_rows(graph_id, &remaining_rows)?;
+            if graph.core_ids.is_empty() || graph.non_core_ids.is_empty() {
+                break;
+            }
+            let prior_rows: Vec<CliqueRow> = match self.drift_sink {
+                Some(_) => seed_rows.clone(),
+                None => Vec::new(),
+            };
+            let scorer: Rc<dyn Scorer<HyperGraph>> = Rc::from(self.build_scorer());
+            let start: Instant = Instant::now();
+            let result: BeamSearchResult<HyperGraph> = self.search_graph(
+                &graph,
+                std::mem::take(&mut seed_rows),
+                graph_id,
+                verbose,
+                Box::new(scorer.clone()),
+            )?;
+            let elapsed: Duration = start.elapsed();
+            // hypergraphs are not pruned.
+            let unpruned_size: (usize, usize) = (graph.count_nodes(), graph.count_edges());
+            let metadata = SearchMetadata::new(
+                &graph,
+                Some(unpruned_size),
+                elapsed,
+                self.get_score_components(&result.top_candidate, scorer.as_ref())?,
+            );
+            self.record_drift(&prior_rows, &graph, &result.top_candidate, scorer.as_ref())?;
+            if result.top_candidate.get_score()? <= 0.0 {
+                break;
+            }
+            let rank: Option<usize> = match self.num_cliques {
+                1 => None,
+                _ => Some(clique_index),
+            };
+            self.print_candidate(&result, graph_id, rank, None, &metadata, output)?;
+            let top_candidate: Candidate<HyperGraph> = result.top_candidate;
+            cliques.push(top_candidate.get_output_rows(graph_id)?);
+            remaining_rows.retain(|r| !top_candidate.core_ids.contains(&r.core_id));
+            for r in remaining_rows.iter_mut() {
+                let (non_core_ids, non_core_type_ids): (Vec<NodeId>, Vec<NodeTypeId>) = r
+                    .non_core_ids
+                    .iter()
+                    .zip(&r.non_core_type_ids)
+                    .filter(|(id, _)| !top_candidate.non_core_ids.contains(id))
+                    .unzip();
+                r.non_core_ids = non_core_ids;
+                r.non_core_type_ids = non_core_type_ids;
+            }
+            remaining_rows.retain(|r| !r.non_core_ids.is_empty());
+        }
+        self.release_node_ids(graph_id);
+        Ok(cliques)
+    }
+    /// forgets the interned node ids of a graph, once all its output has been printed.
+    fn release_node_ids(&self, graph_id: GraphId) {
+        if let Some(interner) = &self.node_id_interner {
+            interner.release(graph_id);
+        }
+    }
+    /// creates a `LineNormalizer`, rewriting input lines as per `self.input_spec`.
+    pub fn get_line_normalizer(&self) -> CLQResult<LineNormalizer> {
+        Ok(self
+            .input_spec
+            .normalizer(Transformer::INPUT_COLUMNS, Transformer::NUM_REQUIRED_INPUT_COLUMNS)?
+            .with_aliases(Transformer::CLIQUE_ROW_INPUT_KEYS))
+    }
+    /// reads graphs from an input (such as stdin), which must provide a lines() function.
+    /// Typical reducer logic: read one line at a time, with the first column being the key.
+    /// If key has not changed, keep accumulating lines. If key has changed, pass the
+    /// accumulated rows to `process`, and reset state. The rows of each graph must be
+    /// contiguous (e.g. sorted by graph_id): a graph_id seen again after its rows ended is
+    /// an error, since its interned node ids (if any) have been released by then.
+    fn read_rows<F>(&self, input: Input, mut process: F) -> CLQResult<()>
+    where
+        F: FnMut(GraphId, Vec<Box<dyn Row>>) -> CLQResult<()>,
+    {
+        self.read_graph_lines(input, |graph_id, lines| {
+            let rows: Vec<Box<dyn Row>> = lines
+                .into_iter()
+                .map(|line| self.process_line(line))
+                .collect::<CLQResult<Vec<Box<dyn Row>>>>()?;
+            process(graph_id, rows)
+        })
+    }
+    /// same as `read_rows`, but passes on the (normalized) lines of each graph unparsed,
+    /// e.g. so that they can be parsed by several transformers.
+    pub(crate) fn read_graph_lines<F>(&self, input: Input, mut process: F) -> CLQResult<()>
+    where
+        F: FnMut(GraphId, Vec<String>) -> CLQResult<()>,
+    {
+        let mut lines: Vec<String> = Vec::new();
         let mut current_graph_id: Option<GraphId> = None;
+        let mut processed_graph_ids: HashSet<GraphId> = HashSet::new();
+        let mut normalizer: LineNormalizer = self.get_line_normalizer()?;
 
         for line in input.lines() {
             match line {
                 Ok(n) => {
-                    let raw: Box<dyn Row> = self.process_line(n)?;
-                    let new_graph_id: GraphId = raw.get_graph_id();
+                    let n: String = match normalizer.normalize(n)? {
+                        Some(n) => n,
+                        // header row
+                        None => continue,
+                    };
+                    let new_graph_id: GraphId = n
+                        .split('\t')
+                        .next()
+                        .ok_or_else(CLQError::err_none)?
+                        .parse::<i64>()?
+                        .into();
                     if let Some(current_id) = current_graph_id {
                         if new_graph_id != current_id {
-                            let graph: TGraph = self.build_pruned_graph::<TGraphBuilder, TGraph>(
-                                current_id, &edge_rows,
-                            )?;
-                            self.process_clique_rows::<TGraphBuilder, TGraph>(
-                                &graph,
-                                clique_rows,
-                                current_id,
-                                // verbose
-                                self.debug,
-                                output,
-                            )?;
-                            edge_rows = Vec::new();
-                            clique_rows = Vec::new();
+                            process(current_id, std::mem::take(&mut lines))?;
+                            processed_graph_ids.insert(current_id);
                         }
                     }
+                    if processed_graph_ids.contains(&new_graph_id) {
+                        return Err(CLQError::from(format!(
+                            "Rows of graph {} are not contiguous: input must be grouped by \
+                             graph_id.",
+                            new_graph_id.value()
+                        )));
+                    }
                     current_graph_id = Some(new_graph_id);
-                    if let Some(r) = raw.as_edge_row() { edge_rows.push(r) }
-                    if let Some(r) = raw.as_clique_row() { clique_rows.push(r) }
+                    lines.push(n);
                 }
                 Err(error) => eprintln!("I/O error: {}", error),
             }
         }
         if let Some(current_id) = current_graph_id {
-            let graph: TGraph =
-                self.build_pruned_graph::<TGraphBuilder, TGraph>(current_id, &edge_rows)?;
-            self.process_clique_rows::<TGraphBuilder, TGraph>(
-                &graph,
+            return process(current_id, lines);
+        }
+        Err("No input rows!".into())
+    }
+    /// same as `read_rows`, but splits the rows of each graph into edge and clique rows.
+    fn read_graphs<F>(&self, input: Input, mut process: F) -> CLQResult<()>
+    where
+        F: FnMut(GraphId, Vec<EdgeRow>, Vec<CliqueRow>) -> CLQResult<()>,
+    {
+        self.read_rows(input, |graph_id, rows| {
+            let mut edge_rows: Vec<EdgeRow> = Vec::new();
+            let mut clique_rows: Vec<CliqueRow> = Vec::new();
+            for raw in rows {
+                if let Some(r) = raw.as_edge_row() { edge_rows.push(r) }
+                if let Some(r) = raw.as_clique_row() { clique_rows.push(r) }
+            }
+            process(graph_id, edge_rows, clique_rows)
+        })
+    }
+    /// to be called by main.rs (or a test), using an input (such as stdin),
+    /// which must provide a lines() function, and an output (such as stdout), to
+    /// which it is possible to write line-by-line. Each graph is processed as soon
+    /// as all its rows have been read. If `self.num_threads` is greater than 1, graphs
+    /// are processed in parallel (see `run_parallel`). If `self.hypergraph` is set, graphs
+    /// are processed as hypergraphs instead (see `run_hypergraph`), which requires
+    /// `self.num_threads` to be 1.
+    pub fn run<TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+        &self,
+        input: Input,
+        output: &mut Output,
+    ) -> CLQResult<()> {
+        if self.hypergraph && self.num_threads > 1 {
+            return Err("Hypergraphs are processed sequentially (num_threads must be 1).".into());
+        }
+        if self.hypergraph {
+            return self.run_hypergraph(input, output);
+        }
+        if self.num_threads > 1 {
+            return self.run_parallel::<TGraphBuilder, TGraph>(input, output);
+        }
+        self.read_graphs(input, |graph_id, edge_rows, clique_rows| {
+            self.process_top_k_cliques::<TGraphBuilder, TGraph>(
+                &edge_rows,
                 clique_rows,
-                current_id,
+                graph_id,
                 // verbose
                 self.debug,
                 output,
             )?;
-            return Ok(());
-        }
-        Err("No input rows!".into())
+            Ok(())
+        })
+    }
+    /// same as `run`, but builds each graph as a `HyperGraph`, from its hyperedge rows.
+    /// Graphs are processed sequentially.
+    pub fn run_hypergraph(&self, input: Input, output: &mut Output) -> CLQResult<()> {
+        self.read_rows(input, |graph_id, rows| {
+            let mut hyperedge_rows: Vec<HyperedgeRow> = Vec::new();
+            let mut clique_rows: Vec<CliqueRow> = Vec::new();
+            for raw in rows {
+                if let Some(r) = raw.as_hyperedge_row() {
+                    hyperedge_rows.push(r)
+                }
+                if let Some(r) = raw.as_clique_row() {
+                    clique_rows.push(r)
+                }
+            }
+            self.process_top_k_hyperedge_cliques(
+                &hyperedge_rows,
+                clique_rows,
+                graph_id,
+                // verbose
+                self.debug,
+                output,
+            )?;
+            Ok(())
+        })
     }
+    /// same as `run`, but dispatches each graph to one of `self.num_threads` worker threads,
+    /// as soon as all its rows have been read. Each worker writes its results to its own
+    /// buffer, and a writer thread prints the buffers in input order, so the output is
+    /// identical to that of a sequential run.
+    pub fn run_parallel<TGraphBuilder: GraphBuilder<TGraph>, TGraph: GraphBase>(
+        &self,
+        input: Input,
+        output: &mut Output,
+    ) -> CLQResult<()> {
+        let num_threads: usize = self.num_threads.max(1);
+        crossbeam::scope(|scope| {
+            // bounded, so that the reader does not get too far ahead of the workers.
+            let (job_sender, job_receiver) =
+                bounded::<(usize, GraphId, Vec<EdgeRow>, Vec<CliqueRow>)>(4 * num_threads);
+            let (result_sender, result_receiver) = unbounded::<(usize, CLQResult<Vec<u8>>)>();
+            for _ in 0..num_threads {
+                let job_receiver = job_receiver.clone();
+                let result_sender = result_sender.clone();
+                scope.spawn(move |_| {
+                    for (ix, graph_id, edge_rows, clique_rows) in job_receiver.iter() {
+                        let mut buffer: Vec<u8> = Vec::new();
+                        let mut graph_output = Output::string(&mut buffer);
+                        let result = self
+                            .process_top_k_cliques::<TGraphBuilder, TGraph>(
+                                &edge_rows,
+                                clique_rows,
+                                graph_id,
+                                // verbose
+                                self.debug,
+                                &mut graph_output,
+                            )
+                            .map(|_| buffer);
+                        if result_sender.send((ix, result)).is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+            // only the workers hold on to jobs, so that sending jobs fails (rather than
+            // blocking) once they have all exited, e.g. if the writer failed.
+            drop(job_receiver);
+            drop(result_sender);
+            let writer = scope.spawn(move |_| -> CLQResult<()> {
+                // results that arrived ahead of their turn, keyed by input order.
+                let mut pending: BTreeMap<usize, CLQResult<Vec<u8>>> = BTreeMap::new();
+                let mut next_ix: usize = 0;
+                for (ix, result) in result_receiver.iter() {
+                    pending.insert(ix, result);
+                    while let Some(result) = pending.remove(&next_ix) {
+                        for line in String::from_utf8(result?)?.lines() {
+                            output.print(line.to_string())?;
+                        }
+                        next_ix += 1;
+                    }
+                }
+                Ok(())
+            });
+            let mut num_graphs: usize = 0;
+            let read_result = self.read_graphs(input, |graph_id, edge_rows, clique_rows| {
+                job_sender
+                    .send((num_graphs, graph_id, edge_rows, clique_rows))
+                    .map_err(|_| CLQError::from("Worker threads exited early."))?;
+                num_graphs += 1;
+                Ok(())
+            });
+            // lets the workers, and then the writer, run out of work.
+            drop(job_sender);
+            let write_result: CLQResult<()> = writer
+                .join()
+                .unwrap_or_else(|_| Err("Writer thread panicked.".into()));
+            // errors raised while processing graphs take precedence, since they cause the
+            // reader to fail as well.
+            write_result.and(read_result)
+        })
+        .unwrap_or_else(|_| Err("Worker thread panicked.".into()))
+    }
+}
+
+/// gets the value of a required command-line argument.
+pub(crate) fn get_required_arg<'a>(matches: &'a ArgMatches, name: &str) -> CLQResult<&'a str> {
+    matches
+        .value_of(name)
+        .ok_or_else(|| CLQError::from(format!("Missing required argument: {}", name)))
 }
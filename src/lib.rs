@@ -15,13 +15,14 @@ pub mod dachshund;
 
 pub use dachshund::beam::Beam;
 pub use dachshund::candidate::Candidate;
+pub use dachshund::exact::ExactSolver;
 pub use dachshund::graph::Graph;
 pub use dachshund::id_types::{GraphId, EdgeTypeId, NodeId, NodeTypeId};
 pub use dachshund::input::Input;
 pub use dachshund::node::Node;
 pub use dachshund::output::Output;
 pub use dachshund::row::EdgeRow;
-pub use dachshund::scorer::Scorer;
+pub use dachshund::scorer::{DefaultScorer, NonCoreCountScorer, Scorer, ScorerType};
 pub use dachshund::simple_transformer::SimpleTransformer;
 pub use dachshund::test_utils::*;
-pub use dachshund::transformer::Transformer;
+pub use dachshund::transformer::{SearchAlgorithm, Transformer};
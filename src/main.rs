@@ -15,6 +15,7 @@ use clap::{App, Arg, ArgMatches};
 use lib_dachshund::dachshund::error::CLQResult;
 use lib_dachshund::dachshund::graph::{TypedGraphBuilder, Graph};
 use lib_dachshund::dachshund::input::Input;
+use lib_dachshund::dachshund::multi_core_transformer::MultiCoreTransformer;
 use lib_dachshund::dachshund::output::Output;
 use lib_dachshund::dachshund::transformer::Transformer;
 
@@ -34,7 +35,9 @@ fn get_command_line_args() -> ArgMatches<'static> {
                  .long("typespec")
                  .takes_value(true)
                  .help("JSON-encoded array of arrays representing Dachshund types. E.g.: \
-                       [[\"author\", \"works_at\", \"university\"], [\"author\", \"published_in\", \"journal\"]]"))
+                       [[\"author\", \"works_at\", \"university\"], [\"author\", \"published_in\", \"journal\"]]. \
+                       Relations may also connect two non-core types, e.g. \
+                       [\"university\", \"located_in\", \"city\"]."))
         .arg(Arg::with_name("beam_size")
                  .short("b")
                  .long("beam_size")
@@ -93,24 +96,220 @@ fn get_command_line_args() -> ArgMatches<'static> {
         .arg(Arg::with_name("core_type")
                  .long("core_type")
                  .takes_value(true)
-                 .help("What the type of the core entity is"))
+                 .help("What the type of the core entity is. Several comma-separated core types \
+                        (e.g. user,merchant) may be provided, in which case (quasi-)cliques are \
+                        mined for each of them, and output rows end with the core type."))
         .arg(Arg::with_name("min_degree")
                  .long("min_degree")
                  .takes_value(true)
                  .help("Min degree for each node in each clique (nodes are pruned iteratively until \
                         all candidate nodes have at least this degree w/r to all other nodes in the \
                         graph"))
+        .arg(Arg::with_name("num_cliques")
+                 .long("num_cliques")
+                 .takes_value(true)
+                 .help("Number of non-overlapping (quasi-)cliques to mine per graph (default = 1). \
+                        The nodes of each clique found are removed from the graph before searching \
+                        for the next one. If greater than 1, each output row is tagged with the \
+                        clique's rank, right after the graph_id."))
+        .arg(Arg::with_name("scorer")
+                 .long("scorer")
+                 .takes_value(true)
+                 .help("Objective maximized by the search: either 'default' (rewards the number of \
+                        core nodes and the diversity of non-core types) or 'non_core_count' \
+                        (rewards the number of core and non-core nodes). Both reward density, as \
+                        per alpha (default = default)."))
+        .arg(Arg::with_name("size_constraints")
+                 .long("size_constraints")
+                 .takes_value(true)
+                 .help("JSON-encoded object of minimum and maximum (null if unbounded) sizes that \
+                        valid (quasi-)cliques must have, per side or per non-core type. E.g.: \
+                        {\"core\": [3, null], \"ip\": [2, 10]}. Keys are either \"core\", \
+                        \"non_core\" or a non-core type."))
+        .arg(Arg::with_name("edge_type_constraints")
+                 .long("edge_type_constraints")
+                 .takes_value(true)
+                 .help("JSON-encoded object of minimum densities of ties of given edge types, \
+                        between core nodes and the non-core nodes they may be tied to this way. \
+                        E.g.: {\"shares_ip\": 1.0} requires each core node to share each IP \
+                        in valid (quasi-)cliques."))
+        .arg(Arg::with_name("edge_type_densities")
+                 .long("edge_type_densities")
+                 .help("If set, output rows end with a JSON-encoded list of the densities of \
+                        each (edge type, non-core type) relation of the typespec, in order \
+                        (null if the (quasi-)clique has no such non-core nodes)."))
+        .arg(Arg::with_name("intern_node_ids")
+                 .long("intern_node_ids")
+                 .takes_value(true)
+                 .help("If provided, node ids may be arbitrary strings (e.g. emails or IPs), \
+                        which are mapped to integers internally, and restored in the output. \
+                        One of: global (ids are shared by all graphs), per_graph (ids are \
+                        forgotten once each graph is processed)."))
+        .arg(Arg::with_name("input_spec")
+                 .long("input_spec")
+                 .takes_value(true)
+                 .help("If provided, a JSON object describing the layout of input lines, e.g. \
+                        {\"delimiter\": \",\", \"header\": true, \"columns\": {\"core_id\": \
+                        \"user_email\"}}. Columns are found by name in the header row (or by \
+                        0-based index if there is none), and the weight and timestamp columns \
+                        may be omitted. There is no support for quoted fields. With \
+                        {\"format\": \"json_lines\"}, each input line is instead a JSON \
+                        object, keyed by column name (seed rows use node_id, node_type and \
+                        clique_id)."))
+        .arg(Arg::with_name("search_metadata")
+                 .long("search_metadata")
+                 .help("If set, output rows end with metadata about the search for each \
+                        (quasi-)clique: its score components, cliqueness, number of search \
+                        steps, early stop reason (if any), the number of nodes and edges of \
+                        the graph before and after pruning, and the search time (in \
+                        milliseconds)."))
+        .arg(Arg::with_name("json_lines_output")
+                 .long("json_lines_output")
+                 .help("If set, each (quasi-)clique is printed as a JSON object on its own \
+                        line, with its graph_id, node ids, types, score, cliqueness, densities \
+                        and number of search steps (not supported in long format)."))
+        .arg(Arg::with_name("max_time_span")
+                 .long("max_time_span")
+                 .takes_value(true)
+                 .help("If provided, the timestamped edges of valid (quasi-)cliques must all \
+                        fall within a window of at most this length (in the units of the \
+                        input timestamps)."))
+        .arg(Arg::with_name("time_span_penalty")
+                 .long("time_span_penalty")
+                 .takes_value(true)
+                 .help("Subtracted from the score of a candidate, per unit of time spanned by \
+                        its timestamped edges (defaults to 0)."))
+        .arg(Arg::with_name("time_span_columns")
+                 .long("time_span_columns")
+                 .help("If set, output rows end with the times of the first and last \
+                        timestamped edges of each (quasi-)clique."))
+        .arg(Arg::with_name("hypergraph")
+                 .long("hypergraph")
+                 .help("If set, each edge row is a hyperedge (e.g. an event), tying the core \
+                        node to a comma-separated list of non-core nodes, with a \
+                        comma-separated list of types (or a single shared type). Cliqueness \
+                        then measures co-occurrence within single hyperedges."))
+        .arg(Arg::with_name("algorithm")
+                 .long("algorithm")
+                 .takes_value(true)
+                 .help("Search algorithm: 'beam' (heuristic beam search), 'exact' (exhaustive \
+                        branch-and-bound search, only practical on small graphs) or 'annealing' \
+                        (simulated annealing with restarts) (default = beam)."))
+        .arg(Arg::with_name("exact_node_budget")
+                 .long("exact_node_budget")
+                 .takes_value(true)
+                 .help("Maximum number of candidates scored by the exact solver, per graph \
+                        (default = 1000000)."))
+        .arg(Arg::with_name("exact_timeout")
+                 .long("exact_timeout")
+                 .takes_value(true)
+                 .help("Maximum number of seconds spent by the exact solver, per graph \
+                        (default = 60). If the budget or timeout runs out, the best (quasi-)clique \
+                        found so far is returned."))
+        .arg(Arg::with_name("annealing_temperature")
+                 .long("annealing_temperature")
+                 .takes_value(true)
+                 .help("Initial temperature of each annealing run (default = 1.0)."))
+        .arg(Arg::with_name("annealing_cooling")
+                 .long("annealing_cooling")
+                 .takes_value(true)
+                 .help("Annealing temperature schedule: 'geometric[:rate]' (the temperature is \
+                        multiplied by rate at each step) or 'linear' (the temperature reaches 0 \
+                        at the last step) (default = geometric:0.99)."))
+        .arg(Arg::with_name("annealing_steps")
+                 .long("annealing_steps")
+                 .takes_value(true)
+                 .help("Number of moves proposed per annealing run (default = 1000)."))
+        .arg(Arg::with_name("annealing_restarts")
+                 .long("annealing_restarts")
+                 .takes_value(true)
+                 .help("Number of annealing runs after the first one, each starting from a seed \
+                        clique or a random node (default = 4). There are at least as many runs \
+                        as seed cliques."))
+        .arg(Arg::with_name("num_threads")
+                 .long("num_threads")
+                 .takes_value(true)
+                 .help("Number of threads used to process graphs in parallel (default = 1). \
+                        Results are written in input order regardless. Not supported for \
+                        hypergraphs."))
+        .arg(Arg::with_name("moves")
+                 .long("moves")
+                 .takes_value(true)
+                 .help("Comma-separated list of moves considered by the beam search: 'add' (add an \
+                        adjacent node), 'remove' (remove the weakest member) and 'swap' (swap the \
+                        weakest member for an adjacent node) (default = add)."))
+        .arg(Arg::with_name("seeding")
+                 .long("seeding")
+                 .takes_value(true)
+                 .help("How the beam is initialized: 'random_walk[:length]' (random walk from a \
+                        random node), 'uniform_random', 'highest_degree' or 'densest_core' \
+                        (nodes from the densest k-core) (default = random_walk:7)."))
+        .arg(Arg::with_name("seed_file")
+                 .long("seed_file")
+                 .takes_value(true)
+                 .help("File of graph_id\tnode_id rows, with which to initialize the beam \
+                        (instead of --seeding). Graphs without seeds fall back to random walks. \
+                        Not supported with --intern_node_ids."))
+        .arg(Arg::with_name("rng_seed")
+                 .long("rng_seed")
+                 .takes_value(true)
+                 .help("Seed for the pseudorandom sequence used by the search, combined with \
+                        each graph_id (by default, only the graph_id is used)."))
+        .arg(Arg::with_name("trace_file")
+                 .long("trace_file")
+                 .takes_value(true)
+                 .help("File to which to write a trace of the beam search, as one JSON object per \
+                        line (epoch starts, candidates considered, expansions scored, beam \
+                        contents and early stops), or of the annealing search (moves proposed, \
+                        as expansions scored, and early stops)."))
+        .arg(Arg::with_name("drift_file")
+                 .long("drift_file")
+                 .takes_value(true)
+                 .help("File to which to write, for each graph with seed cliques, how each of \
+                        them drifted relative to the top candidate found by the search, as one \
+                        JSON object per line (nodes that joined, nodes that left, the seed \
+                        clique's score under the current data, and the Jaccard overlap)."))
+        .arg(Arg::with_name("null_samples")
+                 .long("null_samples")
+                 .takes_value(true)
+                 .help("If set, each (quasi-)clique found is re-scored on this many rewired \
+                        versions of its graph, which preserve the degree sequence as well as \
+                        node and edge types. Each output row then ends with an empirical \
+                        p-value and a z-score."))
+        .arg(Arg::with_name("null_swaps_per_edge")
+                 .long("null_swaps_per_edge")
+                 .takes_value(true)
+                 .help("Number of edge swaps attempted per edge, when rewiring a graph for \
+                        --null_samples (defaults to 10)."))
+        .arg(Arg::with_name("time_budget")
+                 .long("time_budget")
+                 .takes_value(true)
+                 .help("Maximum number of seconds the beam (or annealing) search may spend on each \
+                        graph. If set (or if --expansion_budget is set), the search stops \
+                        gracefully once the budget is exceeded, and each output row ends with a \
+                        column recording whether the search was truncated."))
+        .arg(Arg::with_name("expansion_budget")
+                 .long("expansion_budget")
+                 .takes_value(true)
+                 .help("Maximum number of expansion candidates the beam search may score (or of \
+                        moves the annealing search may propose) for each graph (see \
+                        --time_budget)."))
         .get_matches();
     matches
 }
 
 fn main() -> CLQResult<()> {
     let matches: ArgMatches = get_command_line_args();
-    let transformer = Transformer::from_argmatches(matches)?;
     let stdio: io::Stdin = io::stdin();
     let input: Input = Input::console(&stdio);
     let mut dummy: Vec<u8> = Vec::new();
     let mut output: Output = Output::console(&mut dummy);
-    transformer.run::<TypedGraphBuilder, Graph>(input, &mut output)?;
+    if matches.value_of("core_type").map_or(false, |x| x.contains(',')) {
+        let transformer = MultiCoreTransformer::from_argmatches(matches)?;
+        transformer.run::<TypedGraphBuilder, Graph>(input, &mut output)?;
+    } else {
+        let transformer = Transformer::from_argmatches(matches)?;
+        transformer.run::<TypedGraphBuilder, Graph>(input, &mut output)?;
+    }
     Ok(())
 }
@@ -0,0 +1,244 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate lib_dachshund;
+
+use lib_dachshund::dachshund::annealing::{Annealer, AnnealingSchedule, Cooling};
+use lib_dachshund::dachshund::beam::{BeamSearchResult, SearchBudget};
+use lib_dachshund::dachshund::candidate::Candidate;
+use lib_dachshund::dachshund::error::{CLQError, CLQResult};
+use lib_dachshund::dachshund::graph::{Graph, TypedGraphBuilder};
+use lib_dachshund::dachshund::id_types::{GraphId, NodeId};
+use lib_dachshund::dachshund::output::Output;
+use lib_dachshund::dachshund::row::{CliqueRow, EdgeRow};
+use lib_dachshund::dachshund::scorer::DefaultScorer;
+use lib_dachshund::dachshund::test_utils::{
+    assert_nodes_have_ids, gen_karate_club_edges, gen_single_clique, gen_test_transformer,
+    gen_test_typespec, process_raw_vector,
+};
+use lib_dachshund::dachshund::trace::JsonLinesTraceSink;
+use lib_dachshund::dachshund::transformer::{SearchAlgorithm, Transformer};
+
+fn get_top_candidate<'a>(
+    transformer: &'a Transformer,
+    graph: &'a Graph,
+    graph_id: GraphId,
+) -> CLQResult<Candidate<'a, Graph>> {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    Ok(transformer
+        .process_clique_rows::<TypedGraphBuilder, Graph>(
+            graph,
+            Vec::new(),
+            graph_id,
+            false,
+            &mut output,
+        )?
+        .ok_or_else(CLQError::err_none)?
+        .top_candidate)
+}
+
+#[cfg(test)]
+#[test]
+fn test_annealing_finds_medium_clique() -> CLQResult<()> {
+    let ts = gen_test_typespec();
+    let non_core_types = ts.iter().map(|x| x[2].clone()).collect();
+    let graph_id: GraphId = 0.into();
+    let (core_ids, non_cores, raw) = gen_single_clique(
+        graph_id,
+        10,
+        vec![10, 10],
+        non_core_types,
+        "author".to_string(),
+        vec!["published_at".to_string()],
+    );
+    let mut transformer: Transformer = gen_test_transformer(ts, "author".to_string())?;
+    transformer.algorithm = SearchAlgorithm::Annealing;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let res: Candidate<Graph> = get_top_candidate(&transformer, &graph, graph_id)?;
+    assert_nodes_have_ids(&graph, &res.core_ids, core_ids, true);
+    let non_core_ids: Vec<NodeId> = non_cores.into_iter().map(|x| x.0).collect();
+    assert_nodes_have_ids(&graph, &res.non_core_ids, non_core_ids, false);
+    Ok(())
+}
+
+#[test]
+fn test_annealing_at_least_as_good_as_beam() -> CLQResult<()> {
+    let ts = gen_test_typespec();
+    let non_core_types = ts.iter().map(|x| x[2].clone()).collect();
+    let graph_id: GraphId = 0.into();
+    // a 5x(3+3) biclique, plus a journal only connected to two of the authors.
+    let (_core_ids, _non_cores, mut raw) = gen_single_clique(
+        graph_id,
+        5,
+        vec![3, 3],
+        non_core_types,
+        "author".to_string(),
+        vec!["published_at".to_string()],
+    );
+    raw.push("0\t0\t100\tauthor\tpublished_at\tjournal".to_string());
+    raw.push("0\t1\t100\tauthor\tpublished_at\tjournal".to_string());
+    let mut transformer: Transformer = gen_test_transformer(ts, "author".to_string())?;
+    transformer.global_thresh = Some(0.8);
+    transformer.local_thresh = Some(0.5);
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let beam_score: f32 = get_top_candidate(&transformer, &graph, graph_id)?.get_score()?;
+    transformer.algorithm = SearchAlgorithm::Annealing;
+    transformer.annealing_schedule.cooling = Cooling::Linear;
+    let annealing_score: f32 = get_top_candidate(&transformer, &graph, graph_id)?.get_score()?;
+    assert!(annealing_score >= beam_score);
+    Ok(())
+}
+
+fn run_annealer<'a>(
+    graph: &'a Graph,
+    non_core_types: &'a [String],
+    clique_rows: Vec<CliqueRow>,
+    budget: SearchBudget,
+    tracer: Option<&'a JsonLinesTraceSink<Vec<u8>>>,
+) -> CLQResult<BeamSearchResult<'a, Graph>> {
+    let schedule = AnnealingSchedule {
+        num_steps: 10,
+        num_restarts: 0,
+        ..AnnealingSchedule::default()
+    };
+    let scorer = Box::new(DefaultScorer::new(2, 1.0, Some(1.0), Some(1.0)));
+    let mut annealer: Annealer<Graph> =
+        Annealer::new(graph, clique_rows, scorer, non_core_types, 0.into(), None)?;
+    annealer.budget = budget;
+    if let Some(tracer) = tracer {
+        annealer.tracer = Some(tracer);
+    }
+    annealer.run_search(&schedule)
+}
+
+#[test]
+fn test_annealing_with_seeds_budget_and_tracer() -> CLQResult<()> {
+    let ts = gen_test_typespec();
+    let non_core_types: Vec<String> = ts.iter().map(|x| x[2].clone()).collect();
+    let graph_id: GraphId = 0.into();
+    let (_core_ids, _non_cores, raw) = gen_single_clique(
+        graph_id,
+        5,
+        vec![3, 3],
+        non_core_types.clone(),
+        "author".to_string(),
+        vec!["published_at".to_string()],
+    );
+    let transformer: Transformer = gen_test_transformer(ts, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let seeds: Vec<CliqueRow> = (0..3)
+        .map(|ix| CliqueRow::with_clique_id(graph_id, ix as i64, None, ix))
+        .collect();
+
+    // a single run, unless there are more seed cliques.
+    let no_budget = SearchBudget::default();
+    let result = run_annealer(&graph, &non_core_types, Vec::new(), no_budget, None)?;
+    assert_eq!(result.num_steps, 10);
+    let result = run_annealer(&graph, &non_core_types, seeds.clone(), no_budget, None)?;
+    assert_eq!(result.num_steps, 30);
+    assert!(!result.truncated);
+    assert_eq!(result.stop_reason, None);
+
+    // the budget cuts the second run short, which gets traced.
+    let budget = SearchBudget {
+        time: None,
+        num_expansions: Some(15),
+    };
+    let sink: JsonLinesTraceSink<Vec<u8>> = JsonLinesTraceSink::new(Vec::new());
+    let result = run_annealer(&graph, &non_core_types, seeds, budget, Some(&sink))?;
+    assert_eq!(result.num_steps, 15);
+    assert!(result.truncated);
+    assert_eq!(result.stop_reason, Some("budget_exceeded"));
+    drop(result);
+    let events: Vec<serde_json::Value> = String::from_utf8(sink.into_inner()?)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
+    // single nodes do not meet the thresholds, but are still used as starting points.
+    assert_eq!(events.len(), 19);
+    assert!(events[..3]
+        .iter()
+        .all(|x| x["event"] == "seed_issue" && x["issue"] == "invalid"));
+    assert!(events[3..18].iter().all(|x| x["event"] == "expansion_scored"));
+    assert_eq!(events[18]["event"], "early_stop");
+    assert_eq!(events[18]["epoch"], 1);
+
+    // seed cliques without any node in the graph are reported, and dropped.
+    let seeds: Vec<CliqueRow> = vec![
+        CliqueRow::with_clique_id(graph_id, 0, None, 0),
+        CliqueRow::with_clique_id(graph_id, 9999, None, 1),
+    ];
+    let sink: JsonLinesTraceSink<Vec<u8>> = JsonLinesTraceSink::new(Vec::new());
+    let result = run_annealer(&graph, &non_core_types, seeds, no_budget, Some(&sink))?;
+    assert_eq!(result.num_steps, 10);
+    drop(result);
+    let events: Vec<serde_json::Value> = String::from_utf8(sink.into_inner()?)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
+    assert_eq!(events[1]["event"], "seed_issue");
+    assert_eq!(events[1]["clique_id"], 1);
+    assert_eq!(events[1]["issue"], "no_overlap");
+    assert_eq!(events.len(), 12);
+    assert!(events[2..].iter().all(|x| x["event"] == "expansion_scored"));
+    Ok(())
+}
+
+#[test]
+fn test_annealing_schedule() -> CLQResult<()> {
+    let mut schedule = AnnealingSchedule::default();
+    assert_eq!(schedule.get_temperature(0), 1.0);
+    assert!(schedule.get_temperature(100) < schedule.get_temperature(10));
+    schedule.cooling = "linear".parse()?;
+    schedule.num_steps = 10;
+    assert!((schedule.get_temperature(5) - 0.5).abs() < 1e-6);
+    assert_eq!(
+        "geometric:0.9".parse::<Cooling>()?,
+        Cooling::Geometric(0.9)
+    );
+    assert!("geometric:1.5".parse::<Cooling>().is_err());
+    assert!("exponential".parse::<Cooling>().is_err());
+    assert_eq!("annealing".parse::<SearchAlgorithm>()?, SearchAlgorithm::Annealing);
+    Ok(())
+}
+
+#[test]
+fn test_annealing_on_karate_club() -> CLQResult<()> {
+    // members (core) are tied to the friends (non-core) they have in the karate club, so
+    // that bicliques are sets of members with friends in common.
+    let typespec: Vec<Vec<String>> = vec![vec![
+        "member".to_string(),
+        "is_friends_with".into(),
+        "friend".into(),
+    ]];
+    let graph_id: GraphId = 0.into();
+    let mut raw: Vec<String> = Vec::new();
+    for (s, t) in gen_karate_club_edges() {
+        for &(core_id, non_core_id) in &[(s, t), (t, s)] {
+            raw.push(format!(
+                "0\t{}\t{}\tmember\tis_friends_with\tfriend",
+                core_id,
+                100 + non_core_id
+            ));
+        }
+    }
+    let mut transformer: Transformer = gen_test_transformer(typespec, "member".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let beam_score: f32 = get_top_candidate(&transformer, &graph, graph_id)?.get_score()?;
+    transformer.algorithm = SearchAlgorithm::Annealing;
+    let annealing_score: f32 = get_top_candidate(&transformer, &graph, graph_id)?.get_score()?;
+    assert!(annealing_score >= beam_score);
+    Ok(())
+}
@@ -9,7 +9,7 @@ extern crate lib_dachshund;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 
-use lib_dachshund::dachshund::beam::Beam;
+use lib_dachshund::dachshund::beam::{Beam, SearchBudget, SeedIssue, SeedingStrategy};
 use lib_dachshund::dachshund::candidate::Candidate;
 use lib_dachshund::dachshund::error::{CLQError, CLQResult};
 use lib_dachshund::dachshund::graph::{TypedGraphBuilder, Graph};
@@ -18,9 +18,12 @@ use lib_dachshund::dachshund::input::Input;
 use lib_dachshund::dachshund::output::Output;
 use lib_dachshund::dachshund::row::CliqueRow;
 use lib_dachshund::dachshund::row::EdgeRow;
+use lib_dachshund::dachshund::scorer::DefaultScorer;
 use lib_dachshund::dachshund::test_utils::{
-    assert_nodes_have_ids, gen_test_transformer, process_raw_vector,
+    assert_nodes_have_ids, gen_test_transformer, gen_test_transformer_from_args,
+    process_raw_vector,
 };
+use lib_dachshund::dachshund::trace::JsonLinesTraceSink;
 use lib_dachshund::dachshund::transformer::Transformer;
 
 #[cfg(test)]
@@ -62,11 +65,10 @@ fn test_init_beam_with_clique_rows() -> CLQResult<()> {
         20,
         false,
         &target_types,
-        1,
-        1.0,
-        Some(1.0),
-        Some(1.0),
+        Box::new(DefaultScorer::new(1, 1.0, Some(1.0), Some(1.0))),
         graph_id,
+        &SeedingStrategy::default(),
+        None,
     )?;
     let init_candidate: &Candidate<Graph> = &beam.candidates[0];
     assert_nodes_have_ids(&graph, &init_candidate.core_ids, vec![1], true);
@@ -109,11 +111,10 @@ fn test_init_beam_with_partially_overlapping_clique_rows() -> CLQResult<()> {
         20,
         false,
         &target_types,
-        1,
-        1.0,
-        Some(1.0),
-        Some(1.0),
+        Box::new(DefaultScorer::new(1, 1.0, Some(1.0), Some(1.0))),
         graph_id,
+        &SeedingStrategy::default(),
+        None,
     )?;
     let init_candidate: &Candidate<Graph> = &beam.candidates[0];
     assert_nodes_have_ids(&graph, &init_candidate.core_ids, vec![1], true);
@@ -121,6 +122,55 @@ fn test_init_beam_with_partially_overlapping_clique_rows() -> CLQResult<()> {
     Ok(())
 }
 
+#[test]
+fn test_init_beam_with_multiple_seed_cliques() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published".into(), "article".into()],
+    ];
+    let target_types: Vec<String> = vec!["article".to_string()];
+    let raw = vec![
+        "0\t1\t3\tauthor\tpublished\tarticle".to_string(),
+        "0\t2\t3\tauthor\tpublished\tarticle".into(),
+        "0\t1\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t5\tauthor\tpublished\tarticle".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let article_type: NodeTypeId = *transformer.non_core_type_ids.require("article")?;
+    // clique 2 does not overlap with the graph at all.
+    let clique_rows: Vec<CliqueRow> = vec![
+        CliqueRow::with_clique_id(graph_id, 1, None, 0),
+        CliqueRow::with_clique_id(graph_id, 3, Some(article_type), 0),
+        CliqueRow::with_clique_id(graph_id, 2, None, 1),
+        CliqueRow::with_clique_id(graph_id, 4, Some(article_type), 1),
+        CliqueRow::with_clique_id(graph_id, 8, None, 2),
+        CliqueRow::with_clique_id(graph_id, 7, Some(article_type), 2),
+    ];
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let beam: Beam<Graph> = Beam::new(
+        &graph,
+        clique_rows,
+        20,
+        false,
+        &target_types,
+        Box::new(DefaultScorer::new(1, 1.0, Some(1.0), Some(1.0))),
+        graph_id,
+        &SeedingStrategy::default(),
+        None,
+    )?;
+    // the seed cliques come first, followed by the randomly seeded candidates.
+    assert_eq!(beam.candidates.len(), 20);
+    assert_nodes_have_ids(&graph, &beam.candidates[0].core_ids, vec![1], true);
+    assert_nodes_have_ids(&graph, &beam.candidates[0].non_core_ids, vec![3], false);
+    assert_nodes_have_ids(&graph, &beam.candidates[1].core_ids, vec![2], true);
+    assert_nodes_have_ids(&graph, &beam.candidates[1].non_core_ids, vec![4], false);
+    assert_eq!(beam.seed_issues, vec![(Some(2), SeedIssue::NoOverlap)]);
+    Ok(())
+}
+
 #[test]
 fn test_init_beam_with_clique_rows_input() -> CLQResult<()> {
     let do_test = |should_jumble_rows| -> CLQResult<()> {
@@ -224,3 +274,224 @@ fn test_init_beam_with_clique_rows_input_one_epoch() -> CLQResult<()> {
     assert_eq!(output_str, expected.join("\n") + "\n");
     Ok(())
 }
+
+#[test]
+fn test_init_beam_with_seeding_strategies() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published".into(), "article".into()],
+    ];
+    let target_types: Vec<String> = vec!["article".to_string()];
+    // a 2x2 clique, plus author 6 and article 7, which hang off of it
+    let raw = vec![
+        "0\t1\t3\tauthor\tpublished\tarticle".to_string(),
+        "0\t2\t3\tauthor\tpublished\tarticle".into(),
+        "0\t1\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t4\tauthor\tpublished\tarticle".into(),
+        "0\t1\t5\tauthor\tpublished\tarticle".into(),
+        "0\t6\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t7\tauthor\tpublished\tarticle".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let get_seed_ids = |strategy: &SeedingStrategy, rng_seed: Option<u64>| -> CLQResult<Vec<i64>> {
+        let beam: Beam<Graph> = Beam::new(
+            &graph,
+            Vec::new(),
+            20,
+            false,
+            &target_types,
+            Box::new(DefaultScorer::new(1, 1.0, Some(1.0), Some(1.0))),
+            graph_id,
+            strategy,
+            rng_seed,
+        )?;
+        Ok(beam
+            .candidates
+            .iter()
+            .map(|x| x.get_canonical_ids()[0].value())
+            .collect())
+    };
+    assert_eq!(
+        get_seed_ids(&"highest_degree".parse()?, None)?,
+        vec![1, 2, 4, 3, 5, 6, 7]
+    );
+    assert_eq!(
+        get_seed_ids(&SeedingStrategy::DensestCore, None)?,
+        vec![1, 2, 4, 3]
+    );
+    let seeds = "0\t2\n0\t100\n1\t3\n".to_string();
+    let supplied = SeedingStrategy::from_input(Input::string(seeds.as_bytes()))?;
+    // node 100 is not in the graph
+    assert_eq!(get_seed_ids(&supplied, None)?, vec![2]);
+
+    let uniform_random: SeedingStrategy = "uniform_random".parse()?;
+    assert_eq!(get_seed_ids(&uniform_random, None)?.len(), 20);
+    assert_eq!(
+        get_seed_ids(&uniform_random, Some(42))?,
+        get_seed_ids(&uniform_random, Some(42))?
+    );
+    assert_eq!(
+        "random_walk:3".parse::<SeedingStrategy>()?,
+        SeedingStrategy::RandomWalk(3)
+    );
+    assert!("random_walk:x".parse::<SeedingStrategy>().is_err());
+    assert!("best".parse::<SeedingStrategy>().is_err());
+
+    // supplied seeds are integer ids, which interned node ids would not match.
+    let path = std::env::temp_dir().join(format!(
+        "dachshund_test_seeds_{}.tsv",
+        std::process::id()
+    ));
+    std::fs::write(&path, seeds)?;
+    let seed_file_arg: String = format!("--seed_file={}", path.display());
+    let typespec = r#"[["author", "published", "article"]]"#;
+    let with_seed_file = gen_test_transformer_from_args(typespec, &[&seed_file_arg]);
+    let with_interned_ids = gen_test_transformer_from_args(
+        typespec,
+        &[&seed_file_arg, "--intern_node_ids=per_graph"],
+    );
+    std::fs::remove_file(&path)?;
+    assert!(matches!(
+        with_seed_file?.seeding_strategy,
+        SeedingStrategy::Supplied(_)
+    ));
+    let error: String = with_interned_ids.err().ok_or_else(CLQError::err_none)?.to_string();
+    assert!(error.contains("not supported with interned node ids"));
+    Ok(())
+}
+
+#[test]
+fn test_trace_beam_search() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published".into(), "article".into()],
+    ];
+    let target_types: Vec<String> = vec!["article".to_string()];
+    let raw = vec![
+        "0\t1\t3\tauthor\tpublished\tarticle".to_string(),
+        "0\t2\t3\tauthor\tpublished\tarticle".into(),
+        "0\t1\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t5\tauthor\tpublished\tarticle".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let sink: JsonLinesTraceSink<Vec<u8>> = JsonLinesTraceSink::new(Vec::new());
+    {
+        let mut beam: Beam<Graph> = Beam::new(
+            &graph,
+            Vec::new(),
+            5,
+            false,
+            &target_types,
+            Box::new(DefaultScorer::new(1, 1.0, Some(1.0), Some(1.0))),
+            graph_id,
+            &SeedingStrategy::HighestDegree,
+            None,
+        )?;
+        beam.tracer = Some(&sink);
+        beam.run_search(20, 5, 100, 3)?;
+    }
+    let trace: String = String::from_utf8(sink.into_inner()?)?;
+    let events: Vec<serde_json::Value> = trace
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
+    let kinds: Vec<&str> = events
+        .iter()
+        .map(|x| x["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds[0], "epoch_start");
+    assert_eq!(kinds[1], "candidate_considered");
+    assert!(kinds.contains(&"expansion_scored"));
+    assert!(kinds.contains(&"beam_contents"));
+    assert!(kinds.contains(&"early_stop"));
+
+    let expansion: &serde_json::Value = events
+        .iter()
+        .find(|x| x["event"] == "expansion_scored")
+        .unwrap();
+    assert_eq!(expansion["graph_id"], 0);
+    assert!(expansion["components"]["cliqueness"].is_number());
+    // the last beam contains the 2x2 clique first
+    let beam_contents: &serde_json::Value = events
+        .iter()
+        .rev()
+        .find(|x| x["event"] == "beam_contents")
+        .unwrap();
+    let top: &serde_json::Value = &beam_contents["candidates"][0];
+    assert_eq!(top["core_ids"], serde_json::json!([1, 2]));
+    assert_eq!(top["non_core_ids"], serde_json::json!([3, 4]));
+    assert_eq!(top["non_core_types"], serde_json::json!(["article", "article"]));
+    Ok(())
+}
+
+#[test]
+fn test_beam_search_with_budget() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published".into(), "article".into()],
+    ];
+    let target_types: Vec<String> = vec!["article".to_string()];
+    let raw = vec![
+        "0\t1\t3\tauthor\tpublished\tarticle".to_string(),
+        "0\t2\t3\tauthor\tpublished\tarticle".into(),
+        "0\t1\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t4\tauthor\tpublished\tarticle".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let budget = SearchBudget {
+        time: None,
+        num_expansions: Some(1),
+    };
+    // returns the number of steps, the stop reason and the traced events.
+    let run = |num_epochs: usize| -> CLQResult<(usize, Option<&str>, Vec<serde_json::Value>)> {
+        let sink: JsonLinesTraceSink<Vec<u8>> = JsonLinesTraceSink::new(Vec::new());
+        let (num_steps, stop_reason) = {
+            let mut beam: Beam<Graph> = Beam::new(
+                &graph,
+                Vec::new(),
+                5,
+                false,
+                &target_types,
+                Box::new(DefaultScorer::new(1, 1.0, None, None)),
+                graph_id,
+                &SeedingStrategy::HighestDegree,
+                None,
+            )?;
+            beam.tracer = Some(&sink);
+            beam.budget = budget;
+            let result = beam.run_search(20, 5, num_epochs, 3)?;
+            assert!(result.truncated);
+            (result.num_steps, result.stop_reason)
+        };
+        let events: Vec<serde_json::Value> = String::from_utf8(sink.into_inner()?)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<serde_json::Value>, serde_json::Error>>()?;
+        Ok((num_steps, stop_reason, events))
+    };
+    let count_events = |events: &[serde_json::Value], kind: &str| -> usize {
+        events.iter().filter(|x| x["event"] == kind).count()
+    };
+    // the budget is exceeded in the final (and only) step.
+    let (num_steps, stop_reason, events) = run(1)?;
+    assert_eq!(num_steps, 0);
+    assert_eq!(stop_reason, Some("budget_exceeded"));
+    assert_eq!(count_events(&events, "early_stop"), 1);
+    // the budget is exceeded in the first step, and the final step is skipped.
+    let (num_steps, stop_reason, events) = run(100)?;
+    assert_eq!(num_steps, 1);
+    assert_eq!(stop_reason, Some("budget_exceeded"));
+    assert_eq!(count_events(&events, "early_stop"), 1);
+    assert_eq!(count_events(&events, "epoch_start"), 1);
+    Ok(())
+}
@@ -8,14 +8,14 @@ extern crate lib_dachshund;
 
 use std::collections::HashMap;
 
-use lib_dachshund::dachshund::candidate::Candidate;
+use lib_dachshund::dachshund::candidate::{Candidate, MoveSet, VisitedCandidates};
 use lib_dachshund::dachshund::error::CLQResult;
 use lib_dachshund::dachshund::graph::{TypedGraphBuilder, Graph};
 use lib_dachshund::dachshund::id_types::{GraphId, NodeId};
 use lib_dachshund::dachshund::node::Node;
 use lib_dachshund::dachshund::row::CliqueRow;
 use lib_dachshund::dachshund::row::EdgeRow;
-use lib_dachshund::dachshund::scorer::Scorer;
+use lib_dachshund::dachshund::scorer::{DefaultScorer, Scorer, SizeConstraints, SizeRange};
 use lib_dachshund::dachshund::test_utils::{gen_test_transformer, process_raw_vector};
 use lib_dachshund::dachshund::transformer::Transformer;
 
@@ -62,7 +62,7 @@ fn test_rebuild_candidate() -> CLQResult<()> {
     let non_core_node_id: NodeId = *graph.non_core_ids.first().unwrap();
 
     let alpha: f32 = 1.0;
-    let scorer: Scorer = Scorer::new(2, alpha, Some(0.5), Some(0.5));
+    let scorer: DefaultScorer = DefaultScorer::new(2, alpha, Some(0.5), Some(0.5));
     let mut candidate: Candidate<Graph> = Candidate::new(core_node_id, &graph, &scorer)?;
     candidate.add_node(non_core_node_id)?;
     let score: f32 = scorer.score(&candidate)?;
@@ -80,3 +80,239 @@ fn test_rebuild_candidate() -> CLQResult<()> {
     assert!(candidate.eq(&new_candidate));
     Ok(())
 }
+
+#[test]
+fn test_rebuild_candidate_with_duplicate_rows() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> =
+        vec![vec!["author".to_string(), "published".into(), "article".into()]];
+    let raw: Vec<String> = vec![
+        "0\t1\t3\tauthor\tpublished\tarticle".to_string(),
+        "0\t1\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t3\tauthor\tpublished\tarticle".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let scorer: DefaultScorer = DefaultScorer::new(1, 1.0, None, None);
+    let mut candidate: Candidate<Graph> = Candidate::init_blank(&graph);
+    for &id in &[1, 2, 3, 4] {
+        candidate.add_node(NodeId::from(id as i64))?;
+    }
+    let score: f32 = scorer.score(&candidate)?;
+    candidate.set_score(score)?;
+    // members cannot be added twice.
+    assert!(candidate.add_node(NodeId::from(3)).is_err());
+
+    // each seed row is repeated.
+    let output_rows: Vec<CliqueRow> = candidate.get_output_rows(graph_id)?;
+    let seed_rows: Vec<CliqueRow> = output_rows
+        .iter()
+        .chain(output_rows.iter())
+        .cloned()
+        .collect();
+    let seed: Candidate<Graph> = Candidate::from_clique_rows(seed_rows, &graph, &scorer)?.unwrap();
+    assert!(seed == candidate);
+    assert_eq!(seed.checksum, candidate.checksum);
+    assert_eq!(seed.count_ties_between_nodes()?, 3);
+    assert_eq!(seed.get_member_tie_weight(NodeId::from(1))?, 2.0);
+    assert_eq!(seed.get_score()?, score);
+    Ok(())
+}
+
+#[test]
+fn test_incremental_tie_counts() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published".into(), "article".into()],
+        vec!["author".to_string(), "cited".into(), "article".into()],
+    ];
+    let raw: Vec<String> = vec![
+        "0\t1\t3\tauthor\tpublished\tarticle".to_string(),
+        "0\t1\t3\tauthor\tcited\tarticle\t0.5".into(),
+        "0\t1\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t3\tauthor\tpublished\tarticle".into(),
+        "0\t2\t5\tauthor\tcited\tarticle\t0.25".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+
+    // nodes are added in an order that interleaves core and non-core nodes
+    let mut candidate: Candidate<Graph> = Candidate::init_blank(&graph);
+    for &id in &[3, 1, 5, 2, 4] {
+        candidate.add_node(NodeId::from(id as i64))?;
+        for &node_id in candidate.core_ids.iter() {
+            let node: &Node = candidate.get_node(node_id);
+            assert_eq!(
+                candidate.get_member_tie_weight(node_id)?,
+                node.sum_tie_weights_with_ids(&candidate.non_core_ids)
+            );
+        }
+        for &node_id in candidate.non_core_ids.iter() {
+            let node: &Node = candidate.get_node(node_id);
+            assert_eq!(
+                candidate.get_member_tie_weight(node_id)?,
+                node.sum_tie_weights_with_ids(&candidate.core_ids)
+            );
+        }
+    }
+    assert_eq!(candidate.count_ties_between_nodes()?, 5);
+    assert_eq!(candidate.sum_tie_weights_between_nodes()?, 3.75);
+    // two possible edge types between each of the 2 authors and 3 articles
+    assert_eq!(candidate.get_size()?, 12);
+    assert!(candidate.get_member_tie_weight(NodeId::from(6)).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_checksum_collision() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published".into(), "article".into()],
+    ];
+    let raw: Vec<String> = vec![
+        "0\t1\t3\tauthor\tpublished\tarticle".to_string(),
+        "0\t2\t4\tauthor\tpublished\tarticle".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+
+    let mut first: Candidate<Graph> = Candidate::init_blank(&graph);
+    first.add_node(NodeId::from(1))?;
+    first.add_node(NodeId::from(3))?;
+    let mut second: Candidate<Graph> = Candidate::init_blank(&graph);
+    second.add_node(NodeId::from(2))?;
+    second.add_node(NodeId::from(4))?;
+    // simulate a collision between the checksums of two different candidates
+    second.checksum = first.checksum;
+    assert!(first != second);
+
+    let mut visited: VisitedCandidates = VisitedCandidates::new();
+    assert!(visited.insert(&first));
+    assert!(visited.contains(&first));
+    assert!(!visited.contains(&second));
+    assert!(visited.insert(&second));
+    assert!(!visited.insert(&first.replicate(false)));
+    assert_eq!(visited.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_remove_node() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published".into(), "article".into()],
+        vec!["author".to_string(), "cited".into(), "article".into()],
+    ];
+    let raw: Vec<String> = vec![
+        "0\t1\t3\tauthor\tpublished\tarticle".to_string(),
+        "0\t1\t3\tauthor\tcited\tarticle\t0.5".into(),
+        "0\t1\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t3\tauthor\tpublished\tarticle".into(),
+        "0\t2\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t5\tauthor\tcited\tarticle\t0.25".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+
+    let mut candidate: Candidate<Graph> = Candidate::init_blank(&graph);
+    for &id in &[1, 2, 3, 4, 5] {
+        candidate.add_node(NodeId::from(id as i64))?;
+    }
+    // article 5 only has a single, weak, tie
+    assert_eq!(candidate.get_weakest_member()?, Some(NodeId::from(5)));
+    let mut expected: Candidate<Graph> = Candidate::init_blank(&graph);
+    for &id in &[1, 2, 3, 4] {
+        expected.add_node(NodeId::from(id as i64))?;
+    }
+    candidate.remove_node(NodeId::from(5))?;
+    assert!(candidate == expected);
+    assert_eq!(candidate.checksum, expected.checksum);
+    assert_eq!(candidate.count_ties_between_nodes()?, 5);
+    assert_eq!(candidate.sum_tie_weights_between_nodes()?, 4.5);
+    assert_eq!(candidate.get_size()?, 8);
+    assert_eq!(candidate.get_member_tie_weight(NodeId::from(2))?, 2.0);
+    assert!(candidate.remove_node(NodeId::from(5)).is_err());
+
+    // with only remove moves, the single expansion candidate drops the weakest member
+    let mut candidate: Candidate<Graph> = Candidate::init_blank(&graph);
+    for &id in &[1, 2, 3, 4, 5] {
+        candidate.add_node(NodeId::from(id as i64))?;
+    }
+    let move_set: MoveSet = "remove".parse()?;
+    let scorer: DefaultScorer = DefaultScorer::new(1, 1.0, None, None);
+    let mut visited: VisitedCandidates = VisitedCandidates::new();
+    let expansion_candidates: Vec<Candidate<Graph>> =
+        candidate.one_step_search(20, &mut visited, &scorer, &move_set)?;
+    assert_eq!(expansion_candidates.len(), 1);
+    assert_eq!(
+        expansion_candidates[0].get_canonical_ids(),
+        expected.get_canonical_ids()
+    );
+    assert!("add,jump".parse::<MoveSet>().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_swap_move() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> =
+        vec![vec!["author".to_string(), "published".into(), "article".into()]];
+    // author 2 only published article 3, while author 5 published both articles.
+    let raw: Vec<String> = vec![
+        "0\t1\t3\tauthor\tpublished\tarticle".to_string(),
+        "0\t1\t4\tauthor\tpublished\tarticle".into(),
+        "0\t2\t3\tauthor\tpublished\tarticle".into(),
+        "0\t5\t3\tauthor\tpublished\tarticle".into(),
+        "0\t5\t4\tauthor\tpublished\tarticle".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    // at most two authors, so that author 5 cannot be added.
+    let size_constraints = SizeConstraints {
+        core: SizeRange::new(0, Some(2)),
+        ..SizeConstraints::default()
+    };
+    let scorer: DefaultScorer =
+        DefaultScorer::new(1, 1.0, None, None).with_size_constraints(size_constraints);
+    let mut candidate: Candidate<Graph> = Candidate::init_blank(&graph);
+    for &id in &[1, 2, 3, 4] {
+        candidate.add_node(NodeId::from(id as i64))?;
+    }
+    let score: f32 = scorer.score(&candidate)?;
+    candidate.set_score(score)?;
+    assert_eq!(candidate.get_weakest_member()?, Some(NodeId::from(2)));
+
+    let get_best_score = |move_set: &str| -> CLQResult<f32> {
+        let move_set: MoveSet = move_set.parse()?;
+        let mut visited: VisitedCandidates = VisitedCandidates::new();
+        let expansion_candidates: Vec<Candidate<Graph>> =
+            candidate.one_step_search(20, &mut visited, &scorer, &move_set)?;
+        let mut best_score: f32 = f32::NEG_INFINITY;
+        for expansion_candidate in &expansion_candidates {
+            best_score = best_score.max(expansion_candidate.get_score()?);
+        }
+        Ok(best_score)
+    };
+    // adding author 5 would exceed the maximum, and dropping author 2 costs more than it
+    // gains in density.
+    assert!(get_best_score("add,remove")? < score);
+    // swapping author 2 for author 5 yields a full clique of the same size.
+    let best_score: f32 = get_best_score("swap")?;
+    assert!(best_score > score);
+    let mut expected: Candidate<Graph> = Candidate::init_blank(&graph);
+    for &id in &[1, 5, 3, 4] {
+        expected.add_node(NodeId::from(id as i64))?;
+    }
+    assert_eq!(best_score, scorer.score(&expected)?);
+    Ok(())
+}
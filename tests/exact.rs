@@ -0,0 +1,121 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate lib_dachshund;
+
+use std::time::Duration;
+
+use lib_dachshund::dachshund::beam::BeamSearchResult;
+use lib_dachshund::dachshund::error::{CLQError, CLQResult};
+use lib_dachshund::dachshund::exact::ExactSolver;
+use lib_dachshund::dachshund::graph::{Graph, TypedGraphBuilder};
+use lib_dachshund::dachshund::id_types::{GraphId, NodeId};
+use lib_dachshund::dachshund::output::Output;
+use lib_dachshund::dachshund::row::EdgeRow;
+use lib_dachshund::dachshund::scorer::DefaultScorer;
+use lib_dachshund::dachshund::test_utils::{
+    assert_nodes_have_ids, gen_single_clique, gen_test_transformer, gen_test_typespec,
+    process_raw_vector,
+};
+use lib_dachshund::dachshund::transformer::{SearchAlgorithm, Transformer};
+
+/// a 3x(2+2) biclique, plus a journal only connected to one of the authors.
+fn gen_clique_with_pendant() -> (Vec<NodeId>, Vec<NodeId>, Vec<String>) {
+    let ts = gen_test_typespec();
+    let non_core_types = ts.iter().map(|x| x[2].clone()).collect();
+    let graph_id: GraphId = 0.into();
+    let (core_ids, non_cores, mut raw) = gen_single_clique(
+        graph_id,
+        3,
+        vec![2, 2],
+        non_core_types,
+        "author".to_string(),
+        vec!["published_at".to_string()],
+    );
+    raw.push("0\t0\t100\tauthor\tpublished_at\tjournal".to_string());
+    let non_core_ids: Vec<NodeId> = non_cores.into_iter().map(|x| x.0).collect();
+    (core_ids, non_core_ids, raw)
+}
+
+#[cfg(test)]
+#[test]
+fn test_exact_solver_finds_clique() -> CLQResult<()> {
+    let (core_ids, non_core_ids, raw) = gen_clique_with_pendant();
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(gen_test_typespec(), "author".into())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let mut solver: ExactSolver<Graph> = ExactSolver::new(
+        &graph,
+        Box::new(DefaultScorer::new(2, 1.0, Some(1.0), Some(1.0))),
+        1_000_000,
+        Duration::from_secs(60),
+    );
+    let result: BeamSearchResult<Graph> = solver.run_search()?;
+    assert!(solver.exhausted_search());
+    assert_nodes_have_ids(&graph, &result.top_candidate.core_ids, core_ids, true);
+    assert_nodes_have_ids(&graph, &result.top_candidate.non_core_ids, non_core_ids, false);
+    Ok(())
+}
+
+#[test]
+fn test_exact_solver_out_of_budget() -> CLQResult<()> {
+    let (_core_ids, _non_core_ids, raw) = gen_clique_with_pendant();
+    let graph_id: GraphId = 0.into();
+    let transformer: Transformer = gen_test_transformer(gen_test_typespec(), "author".into())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let mut solver: ExactSolver<Graph> = ExactSolver::new(
+        &graph,
+        Box::new(DefaultScorer::new(2, 1.0, Some(1.0), Some(1.0))),
+        5,
+        Duration::from_secs(60),
+    );
+    let result: BeamSearchResult<Graph> = solver.run_search()?;
+    assert!(!solver.exhausted_search());
+    assert_eq!(result.num_steps, 5);
+    Ok(())
+}
+
+#[test]
+fn test_exact_search_at_least_as_good_as_beam() -> CLQResult<()> {
+    let (_core_ids, _non_core_ids, raw) = gen_clique_with_pendant();
+    let graph_id: GraphId = 0.into();
+    let mut transformer: Transformer =
+        gen_test_transformer(gen_test_typespec(), "author".into())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    let beam_score: f32 = transformer
+        .process_clique_rows::<TypedGraphBuilder, Graph>(
+            &graph,
+            Vec::new(),
+            graph_id,
+            false,
+            &mut output,
+        )?
+        .ok_or_else(CLQError::err_none)?
+        .top_candidate
+        .get_score()?;
+    transformer.algorithm = SearchAlgorithm::Exact;
+    let exact_score: f32 = transformer
+        .process_clique_rows::<TypedGraphBuilder, Graph>(
+            &graph,
+            Vec::new(),
+            graph_id,
+            false,
+            &mut output,
+        )?
+        .ok_or_else(CLQError::err_none)?
+        .top_candidate
+        .get_score()?;
+    assert!(exact_score >= beam_score);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate lib_dachshund;
+
+use lib_dachshund::dachshund::candidate::Candidate;
+use lib_dachshund::dachshund::error::CLQResult;
+use lib_dachshund::dachshund::graph::{Graph, GraphBase, TypedGraphBuilder};
+use lib_dachshund::dachshund::hypergraph::{HyperGraph, HyperGraphBuilder};
+use lib_dachshund::dachshund::id_types::{GraphId, NodeId};
+use lib_dachshund::dachshund::input::Input;
+use lib_dachshund::dachshund::output::Output;
+use lib_dachshund::dachshund::row::HyperedgeRow;
+use lib_dachshund::dachshund::scorer::DefaultScorer;
+use lib_dachshund::dachshund::test_utils::gen_test_transformer;
+use lib_dachshund::dachshund::transformer::Transformer;
+
+fn gen_test_hypergraph_transformer() -> CLQResult<Transformer> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["user".to_string(), "logged_in".into(), "device".into()],
+        vec!["user".to_string(), "logged_in".into(), "ip".into()],
+    ];
+    let mut transformer: Transformer = gen_test_transformer(typespec, "user".into())?;
+    transformer.hypergraph = true;
+    Ok(transformer)
+}
+
+/// users 1-3 each log in once with device 11 from ip 21. User 4 uses both, but in
+/// separate logins, so every pairwise tie exists without any co-occurrence.
+fn gen_test_hyperedge_lines() -> Vec<String> {
+    let mut raw: Vec<String> = Vec::new();
+    for core_id in 1..4 {
+        raw.push(format!("0\t{}\t11,21\tuser\tlogged_in\tdevice,ip", core_id));
+    }
+    raw.push("0\t4\t11\tuser\tlogged_in\tdevice".into());
+    raw.push("0\t4\t21\tuser\tlogged_in\tip".into());
+    raw
+}
+
+#[cfg(test)]
+#[test]
+fn test_hyperedge_cliqueness() -> CLQResult<()> {
+    let transformer: Transformer = gen_test_hypergraph_transformer()?;
+    let mut rows: Vec<HyperedgeRow> = Vec::new();
+    for line in gen_test_hyperedge_lines() {
+        rows.push(transformer.process_line(line)?.as_hyperedge_row().unwrap());
+    }
+    let graph: HyperGraph = HyperGraphBuilder::from_rows(GraphId::from(0), &rows)?;
+    assert_eq!(graph.core_ids.len(), 4);
+    assert_eq!(graph.non_core_ids.len(), 2);
+    assert_eq!(graph.count_hyperedges(), 5);
+    // the pairwise projection has one tie per (user, non-core node) pair, in each direction.
+    assert_eq!(graph.count_edges(), 16);
+
+    let scorer = DefaultScorer::new(transformer.num_non_core_types, 1.0, None, None);
+    let mut candidate: Candidate<HyperGraph> = Candidate::init_blank(&graph);
+    for &id in &[1, 11, 21] {
+        candidate.add_node(NodeId::from(id as i64))?;
+    }
+    assert_eq!(candidate.get_hyperedge_cliqueness()?, Some(1.0));
+    candidate.add_node(NodeId::from(4))?;
+    // user 4 covers half of the non-core nodes in any single login.
+    assert_eq!(candidate.get_cliqueness()?, 1.0);
+    assert_eq!(candidate.get_hyperedge_cliqueness()?, Some(0.75));
+    assert_eq!(scorer.get_cliqueness(&candidate)?, 0.75);
+
+    // a non-core node cannot be used with several types.
+    let row = transformer.process_line("0\t5\t11\tuser\tlogged_in\tip".into())?;
+    rows.push(row.as_hyperedge_row().unwrap());
+    assert!(HyperGraphBuilder::from_rows(GraphId::from(0), &rows).is_err());
+    assert!(transformer
+        .process_line("0\t1\t11,21,31\tuser\tlogged_in\tdevice,ip".into())
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_run_hypergraph() -> CLQResult<()> {
+    let mut transformer: Transformer = gen_test_hypergraph_transformer()?;
+    let text: String = gen_test_hyperedge_lines().join("\n");
+    let input = Input::string(text.as_bytes());
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(input, &mut output)?;
+    let lines: Vec<String> = String::from_utf8(buffer)?
+        .lines()
+        .map(|x| x.to_string())
+        .collect();
+    assert_eq!(lines.len(), 1);
+    let columns: Vec<&str> = lines[0].split('\t').collect();
+    // user 4 would belong to the clique if co-occurrence were flattened into pairwise ties.
+    assert_eq!(columns[3], "[1,2,3]");
+    assert_eq!(columns[4], "[11,21]");
+
+    // hypergraphs are not processed in parallel.
+    transformer.num_threads = 2;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    assert!(transformer
+        .run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,138 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate lib_dachshund;
+
+use lib_dachshund::dachshund::error::CLQResult;
+use lib_dachshund::dachshund::graph::{Graph, TypedGraphBuilder};
+use lib_dachshund::dachshund::input::Input;
+use lib_dachshund::dachshund::input_spec::InputSpec;
+use lib_dachshund::dachshund::output::Output;
+use lib_dachshund::dachshund::simple_transformer::{
+    LineProcessor, SimpleTransformer, TransformerBase,
+};
+use lib_dachshund::dachshund::test_utils::gen_test_transformer;
+use lib_dachshund::dachshund::transformer::Transformer;
+
+fn gen_test_typed_transformer() -> CLQResult<Transformer> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published".into(), "article".into()],
+        vec![
+            "author".to_string(),
+            "published_in".into(),
+            "journal".into(),
+        ],
+    ];
+    let mut transformer: Transformer = gen_test_transformer(typespec, "author".into())?;
+    transformer.debug = false;
+    Ok(transformer)
+}
+
+fn run_typed(transformer: &Transformer, text: &str) -> CLQResult<String> {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)?;
+    // drops the non-core types column, whose order is not deterministic.
+    Ok(String::from_utf8(buffer)?
+        .lines()
+        .map(|line| {
+            let mut columns: Vec<&str> = line.split('\t').collect();
+            columns.remove(5);
+            columns.join("\t")
+        })
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+#[test]
+fn test_run_with_input_spec() -> CLQResult<()> {
+    let mut tsv: Vec<String> = Vec::new();
+    // as exported from a warehouse: reordered, renamed, and with an extra column.
+    let mut csv: Vec<String> =
+        vec!["relation,author_id,graph,target_id,target_type,author_type,extra".to_string()];
+    for author in 1..4 {
+        for &(target, target_type, relation) in &[
+            (11, "article", "published"),
+            (12, "article", "published"),
+            (21, "journal", "published_in"),
+        ] {
+            tsv.push(format!(
+                "0\t{}\t{}\tauthor\t{}\t{}",
+                author, target, relation, target_type
+            ));
+            csv.push(format!(
+                "{},{},0,{},{},author,whatever",
+                relation, author, target, target_type
+            ));
+        }
+    }
+    let mut transformer: Transformer = gen_test_typed_transformer()?;
+    let expected: String = run_typed(&transformer, &tsv.join("\n"))?;
+    assert_eq!(expected.lines().count(), 1);
+
+    transformer.input_spec = InputSpec::from_json(
+        r#"{"delimiter": ",", "header": true, "columns": {"graph_id": "graph",
+            "core_id": "author_id", "non_core_id": "target_id", "core_type": "author_type",
+            "edge_type": "relation", "non_core_type": "target_type"}}"#,
+    )?;
+    assert_eq!(run_typed(&transformer, &csv.join("\n"))?, expected);
+    // tabs would be read as column separators once normalized.
+    let mut with_tab: Vec<String> = csv.clone();
+    with_tab.push("published,1,0,11\t12,article,author,whatever".into());
+    let err = run_typed(&transformer, &with_tab.join("\n")).unwrap_err();
+    assert!(err.to_string().contains("cannot contain tabs"));
+
+    // the same, without a header row, with columns given by index. Unmapped columns keep
+    // their usual positions, so the extra column would be read as weights.
+    transformer.input_spec = InputSpec::from_json(
+        r#"{"delimiter": ",", "columns": {"graph_id": 2, "core_id": 1, "non_core_id": 3,
+            "core_type": 5, "edge_type": 0, "non_core_type": 4}}"#,
+    )?;
+    assert!(run_typed(&transformer, &csv[1..].join("\n")).is_err());
+    let headerless: Vec<String> = csv[1..]
+        .iter()
+        .map(|x| x.trim_end_matches(",whatever").to_string())
+        .collect();
+    assert_eq!(run_typed(&transformer, &headerless.join("\n"))?, expected);
+
+    // required columns must be found.
+    transformer.input_spec = InputSpec::from_json(r#"{"delimiter": ",", "header": true}"#)?;
+    assert!(run_typed(&transformer, &csv.join("\n")).is_err());
+    transformer.input_spec = InputSpec::from_json(r#"{"columns": {"user_id": "author_id"}}"#)?;
+    assert!(run_typed(&transformer, &tsv.join("\n")).is_err());
+    assert!(InputSpec::from_json(r#"{"delimiter": ",,"}"#).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_simple_transformer_with_input_spec() -> CLQResult<()> {
+    let edges: Vec<(usize, usize)> = vec![(0, 1), (0, 2), (1, 2), (2, 3), (3, 4), (4, 5), (3, 5)];
+    let tsv: String = edges
+        .iter()
+        .map(|(s, t)| format!("g\t{}\t{}", s, t))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let csv: String = std::iter::once("dst;src;graph".to_string())
+        .chain(edges.iter().map(|(s, t)| format!("{};{};g", t, s)))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let run = |mut transformer: SimpleTransformer, text: &str| -> CLQResult<String> {
+        let mut buffer: Vec<u8> = Vec::new();
+        transformer.run(Input::string(text.as_bytes()), Output::string(&mut buffer))?;
+        Ok(String::from_utf8(buffer)?)
+    };
+    let expected: String = run(SimpleTransformer::new(), &tsv)?;
+    let input_spec = InputSpec::from_json(
+        r#"{"delimiter": ";", "header": true,
+            "columns": {"source_id": "src", "target_id": "dst", "graph_id": "graph"}}"#,
+    )?;
+    let line_processor = LineProcessor::new().with_input_spec(input_spec);
+    assert_eq!(
+        run(SimpleTransformer::with_line_processor(line_processor), &csv)?,
+        expected
+    );
+    Ok(())
+}
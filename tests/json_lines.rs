@@ -0,0 +1,120 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate lib_dachshund;
+extern crate serde_json;
+
+use lib_dachshund::dachshund::error::CLQResult;
+use lib_dachshund::dachshund::graph::{Graph, TypedGraphBuilder};
+use lib_dachshund::dachshund::input::Input;
+use lib_dachshund::dachshund::input_spec::InputSpec;
+use lib_dachshund::dachshund::interner::{InternerScope, NodeIdInterner};
+use lib_dachshund::dachshund::output::Output;
+use lib_dachshund::dachshund::simple_transformer::{
+    LineProcessor, SimpleTransformer, TransformerBase,
+};
+use lib_dachshund::dachshund::test_utils::gen_test_transformer;
+use lib_dachshund::dachshund::transformer::Transformer;
+use serde_json::{json, Value};
+
+fn run_typed(transformer: &Transformer, text: &str) -> CLQResult<Vec<String>> {
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)?;
+    Ok(String::from_utf8(buffer)?
+        .lines()
+        .map(|x| x.to_string())
+        .collect())
+}
+
+#[test]
+fn test_run_with_json_lines() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![vec![
+        "author".to_string(),
+        "published".into(),
+        "article".into(),
+    ]];
+    let mut transformer: Transformer = gen_test_transformer(typespec, "author".into())?;
+    transformer.debug = false;
+    let mut tsv: Vec<String> = Vec::new();
+    let mut json_lines: Vec<String> = Vec::new();
+    for author in 1..4 {
+        for article in 11..13 {
+            tsv.push(format!(
+                "0\t{}\t{}\tauthor\tpublished\tarticle\t\t{}",
+                author, article, article
+            ));
+            json_lines.push(
+                json!({"graph_id": 0, "core_id": author, "non_core_id": article,
+                    "core_type": "author", "edge_type": "published",
+                    "non_core_type": "article", "timestamp": article})
+                .to_string(),
+            );
+        }
+    }
+    tsv.push("0\t1\tauthor\t\t\t".into());
+    json_lines.push(json!({"graph_id": 0, "node_id": 1, "node_type": "author"}).to_string());
+    transformer.time_span_columns = true;
+    let expected: Vec<String> = run_typed(&transformer, &tsv.join("\n"))?;
+    assert_eq!(expected.len(), 1);
+
+    transformer.input_spec = InputSpec::from_json(r#"{"format": "json_lines"}"#)?;
+    assert_eq!(run_typed(&transformer, &json_lines.join("\n"))?, expected);
+
+    transformer.json_lines_output = true;
+    let lines: Vec<String> = run_typed(&transformer, &json_lines.join("\n"))?;
+    assert_eq!(lines.len(), 1);
+    let object: Value = serde_json::from_str(&lines[0])?;
+    assert_eq!(object["graph_id"], json!(0));
+    assert_eq!(object["core_ids"], json!([1, 2, 3]));
+    assert_eq!(object["non_core_ids"], json!([11, 12]));
+    assert_eq!(object["non_core_types"], json!(["article", "article"]));
+    assert_eq!(object["cliqueness"], json!(1));
+    // floats are printed as in tab-separated output.
+    assert_eq!(object["core_densities"], json!([1, 1, 1]));
+    assert_eq!(object["first_timestamp"], json!(11));
+    assert_eq!(object["last_timestamp"], json!(12));
+    assert!(object["score"].as_f64().unwrap() > 0.0);
+    assert!(object["num_steps"].is_u64());
+
+    // interned ids are printed as strings, even if they look like numbers.
+    transformer.node_id_interner = Some(NodeIdInterner::new(InternerScope::PerGraph));
+    let lines: Vec<String> = run_typed(&transformer, &json_lines.join("\n"))?;
+    let object: Value = serde_json::from_str(&lines[0])?;
+    assert_eq!(object["core_ids"], json!(["1", "2", "3"]));
+    assert_eq!(object["non_core_ids"], json!(["11", "12"]));
+    transformer.node_id_interner = None;
+
+    // objects must be flat.
+    let invalid: String = json!({"graph_id": 0, "core_id": {"id": 1}}).to_string();
+    assert!(run_typed(&transformer, &invalid).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_simple_transformer_with_json_lines() -> CLQResult<()> {
+    let edges: Vec<(usize, usize)> = vec![(0, 1), (0, 2), (1, 2), (2, 3)];
+    let text: String = edges
+        .iter()
+        .map(|(s, t)| json!({"graph": "g", "source_id": s, "target_id": t}).to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    let input_spec =
+        InputSpec::from_json(r#"{"format": "json_lines", "columns": {"graph_id": "graph"}}"#)?;
+    let line_processor = LineProcessor::new()
+        .with_input_spec(input_spec)
+        .with_json_lines_output();
+    let mut transformer = SimpleTransformer::with_line_processor(line_processor);
+    let mut buffer: Vec<u8> = Vec::new();
+    transformer.run(Input::string(text.as_bytes()), Output::string(&mut buffer))?;
+    let output_str: String = String::from_utf8(buffer)?;
+    let object: Value = serde_json::from_str(output_str.trim_end())?;
+    assert_eq!(object["graph_id"], json!("g"));
+    // edges are counted in both directions.
+    assert_eq!(object["num_edges"], json!(8));
+    assert_eq!(object["size_of_largest_cc"], json!(4));
+    Ok(())
+}
@@ -14,92 +14,18 @@ use lib_dachshund::dachshund::graph::{
 };
 use lib_dachshund::dachshund::id_types::{GraphId, NodeId};
 use lib_dachshund::dachshund::row::EdgeRow;
-use lib_dachshund::dachshund::test_utils::{gen_test_transformer, process_raw_vector};
+use lib_dachshund::dachshund::test_utils::{
+    gen_karate_club_edges, gen_test_transformer, process_raw_vector,
+};
 use lib_dachshund::dachshund::transformer::Transformer;
 use std::collections::HashSet;
 use test::Bencher;
 
 fn get_rows(transformer: &Transformer) -> Vec<EdgeRow> {
-    let mut raw = vec![
-        "0\t1\t2\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t3\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t2\t3\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t4\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t2\t4\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t3\t4\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t5\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t6\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t7\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t5\t7\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t6\t7\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t8\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t2\t8\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t3\t8\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t4\t8\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t9\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t3\t9\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t3\t10\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t11\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t5\t11\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t6\t11\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t12\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t13\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t4\t13\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t14\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t2\t14\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t3\t14\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t4\t14\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t6\t17\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t7\t17\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t18\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t2\t18\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t20\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t2\t20\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t22\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t2\t22\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t24\t26\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t25\t26\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t3\t28\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t24\t28\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t25\t28\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t3\t29\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t24\t30\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t27\t30\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t2\t31\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t9\t31\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t1\t32\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t25\t32\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t26\t32\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t29\t32\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t3\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t9\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t15\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t16\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t19\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t21\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t23\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t24\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t30\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t31\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t32\t33\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t9\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t10\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t14\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t15\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t16\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t19\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t20\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t21\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t23\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t24\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t27\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t28\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t29\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t30\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t31\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t32\t34\tstudent\tis_friends_with\tstudent".to_string(),
-        "0\t33\t34\tstudent\tis_friends_with\tstudent".to_string(),
-    ];
+    let mut raw: Vec<String> = gen_karate_club_edges()
+        .into_iter()
+        .map(|(s, t)| format!("0\t{}\t{}\tstudent\tis_friends_with\tstudent", s, t))
+        .collect();
     let mut reversed: Vec<String> = Vec::new();
     for el in &raw {
         let mut vec: Vec<&str> = el.split('\t').collect();
@@ -136,6 +62,8 @@ fn get_karate_club_graph_with_one_extra_edge() -> SimpleUndirectedGraph {
         source_type_id: rows[0].source_type_id,
         target_type_id: rows[0].target_type_id,
         edge_type_id: rows[0].edge_type_id,
+        weight: rows[0].weight,
+        timestamp: rows[0].timestamp,
     };
     let rev_edge = EdgeRow {
         graph_id: rows[0].graph_id,
@@ -144,6 +72,8 @@ fn get_karate_club_graph_with_one_extra_edge() -> SimpleUndirectedGraph {
         source_type_id: rows[0].source_type_id,
         target_type_id: rows[0].target_type_id,
         edge_type_id: rows[0].edge_type_id,
+        weight: rows[0].weight,
+        timestamp: rows[0].timestamp,
     };
     rows.push(new_edge);
     rows.push(rev_edge);
@@ -165,6 +95,8 @@ fn get_two_karate_clubs_edges(transformer: &Transformer) -> Vec<EdgeRow> {
             source_type_id: rows[0].source_type_id,
             target_type_id: rows[0].target_type_id,
             edge_type_id: rows[0].edge_type_id,
+            weight: rows[0].weight,
+            timestamp: rows[0].timestamp,
         };
         rows.push(new_edge);
     }
@@ -196,6 +128,8 @@ fn get_two_karate_clubs_with_bridge() -> SimpleUndirectedGraph {
         source_type_id: rows[0].source_type_id,
         target_type_id: rows[0].target_type_id,
         edge_type_id: rows[0].edge_type_id,
+        weight: rows[0].weight,
+        timestamp: rows[0].timestamp,
     };
     let rev_edge = EdgeRow {
         graph_id: rows[0].graph_id,
@@ -204,6 +138,8 @@ fn get_two_karate_clubs_with_bridge() -> SimpleUndirectedGraph {
         source_type_id: rows[0].source_type_id,
         target_type_id: rows[0].target_type_id,
         edge_type_id: rows[0].edge_type_id,
+        weight: rows[0].weight,
+        timestamp: rows[0].timestamp,
     };
     rows.push(new_edge);
     rows.push(rev_edge);
@@ -0,0 +1,96 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate lib_dachshund;
+
+use lib_dachshund::dachshund::error::CLQResult;
+use lib_dachshund::dachshund::graph::{Graph, TypedGraphBuilder};
+use lib_dachshund::dachshund::input::Input;
+use lib_dachshund::dachshund::multi_core_transformer::MultiCoreTransformer;
+use lib_dachshund::dachshund::output::Output;
+use lib_dachshund::dachshund::test_utils::gen_test_transformer;
+use lib_dachshund::dachshund::transformer::Transformer;
+
+fn gen_typespec() -> Vec<Vec<String>> {
+    vec![
+        vec!["user".to_string(), "uses".into(), "device".into()],
+        vec!["merchant".to_string(), "uses".into(), "device".into()],
+        vec!["device".to_string(), "located_in".into(), "city".into()],
+    ]
+}
+
+#[cfg(test)]
+#[test]
+fn test_split_typespec() -> CLQResult<()> {
+    let core_types: Vec<String> = vec!["user".into(), "merchant".into()];
+    let typespecs = MultiCoreTransformer::split_typespec(&gen_typespec(), &core_types)?;
+    assert_eq!(typespecs.len(), 2);
+    assert_eq!(typespecs[0][0][0], "user");
+    assert_eq!(typespecs[1][0][0], "merchant");
+    // both core types reach the city relation, through devices.
+    assert_eq!(typespecs[0][1][2], "city");
+    assert_eq!(typespecs[1][1][2], "city");
+
+    let mut typespec = gen_typespec();
+    typespec.push(vec!["venue".to_string(), "located_in".into(), "city".into()]);
+    assert!(MultiCoreTransformer::split_typespec(&typespec, &core_types).is_err());
+    let core_types: Vec<String> = vec!["user".into(), "admin".into()];
+    assert!(MultiCoreTransformer::split_typespec(&gen_typespec(), &core_types).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_run_multiple_core_types() -> CLQResult<()> {
+    let core_types: Vec<String> = vec!["user".into(), "merchant".into()];
+    let typespecs = MultiCoreTransformer::split_typespec(&gen_typespec(), &core_types)?;
+    let mut transformers: Vec<Transformer> = Vec::new();
+    for (core_type, typespec) in core_types.into_iter().zip(typespecs) {
+        let mut transformer: Transformer = gen_test_transformer(typespec, core_type)?;
+        transformer.core_type_column = true;
+        transformers.push(transformer);
+    }
+    let multi_core_transformer = MultiCoreTransformer { transformers };
+    // users 1-2 share devices 11-12, merchants 5-6 share devices 12-13.
+    let mut raw: Vec<String> = Vec::new();
+    for &(core_type, core_ids, device_ids) in &[
+        ("user", [1, 2], [11, 12]),
+        ("merchant", [5, 6], [12, 13]),
+    ] {
+        for core_id in &core_ids {
+            for device_id in &device_ids {
+                raw.push(format!("0\t{}\t{}\t{}\tuses\tdevice", core_id, device_id, core_type));
+            }
+        }
+    }
+    let text: String = raw.join("\n");
+    let input = Input::string(text.as_bytes());
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    multi_core_transformer.run::<TypedGraphBuilder, Graph>(input, &mut output)?;
+    let lines: Vec<String> = String::from_utf8(buffer)?
+        .lines()
+        .map(|x| x.to_string())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    let columns: Vec<&str> = lines[0].split('\t').collect();
+    assert_eq!(columns[3], "[1,2]");
+    assert_eq!(columns[4], "[11,12]");
+    assert_eq!(columns[columns.len() - 1], "user");
+    let columns: Vec<&str> = lines[1].split('\t').collect();
+    assert_eq!(columns[3], "[5,6]");
+    assert_eq!(columns[4], "[12,13]");
+    assert_eq!(columns[columns.len() - 1], "merchant");
+
+    // graphs are processed sequentially.
+    let mut multi_core_transformer = multi_core_transformer;
+    multi_core_transformer.transformers[1].num_threads = 2;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    assert!(multi_core_transformer
+        .run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)
+        .is_err());
+    Ok(())
+}
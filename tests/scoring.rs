@@ -7,14 +7,19 @@
 extern crate lib_dachshund;
 
 use lib_dachshund::dachshund::candidate::Candidate;
-use lib_dachshund::dachshund::error::CLQResult;
+use lib_dachshund::dachshund::error::{CLQError, CLQResult};
 use lib_dachshund::dachshund::graph::{TypedGraphBuilder, Graph};
 use lib_dachshund::dachshund::id_types::{GraphId, NodeId};
+use lib_dachshund::dachshund::output::Output;
 use lib_dachshund::dachshund::row::EdgeRow;
-use lib_dachshund::dachshund::scorer::Scorer;
+use lib_dachshund::dachshund::scorer::{
+    DefaultScorer, Scorer, ScorerType, SizeConstraints, TimeWindow,
+};
 use lib_dachshund::dachshund::transformer::Transformer;
 
-use lib_dachshund::dachshund::test_utils::{gen_test_transformer, process_raw_vector};
+use lib_dachshund::dachshund::test_utils::{
+    gen_test_transformer, gen_test_typespec, process_raw_vector,
+};
 
 #[cfg(test)]
 #[test]
@@ -33,7 +38,7 @@ fn test_score_trivial_graph() -> CLQResult<()> {
     assert_eq!(graph.non_core_ids.len(), 1);
 
     let alpha: f32 = 1.0;
-    let scorer: Scorer = Scorer::new(2, alpha, Some(0.5), Some(0.5));
+    let scorer: DefaultScorer = DefaultScorer::new(2, alpha, Some(0.5), Some(0.5));
     let core_node_id: NodeId = *graph.core_ids.first().unwrap();
     let mut candidate: Candidate<Graph> = Candidate::new(core_node_id, &graph, &scorer)?;
     assert_eq!(candidate.get_score()?, -1.0);
@@ -73,3 +78,314 @@ fn test_score_trivial_graph() -> CLQResult<()> {
     assert_eq!(score, expected_score);
     Ok(())
 }
+
+#[test]
+fn test_score_weighted_graph() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![vec![
+        "author".to_string(),
+        "published_at".into(),
+        "conference".into(),
+    ]];
+    let graph_id: GraphId = 0.into();
+    let raw: Vec<String> = vec![
+        "0\t1\t3\tauthor\tpublished_at\tconference\t1.0".to_string(),
+        "0\t1\t4\tauthor\tpublished_at\tconference\t0.5".into(),
+        "0\t2\t3\tauthor\tpublished_at\tconference\t0.25".into(),
+        // unweighted edges count as 1.0
+        "0\t2\t4\tauthor\tpublished_at\tconference".into(),
+    ];
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+
+    let alpha: f32 = 1.0;
+    let scorer: DefaultScorer = DefaultScorer::new(1, alpha, Some(0.5), Some(0.5));
+    let mut candidate: Candidate<Graph> = Candidate::init_blank(&graph);
+    for &node_id in graph.core_ids.iter().chain(graph.non_core_ids.iter()) {
+        candidate.add_node(node_id)?;
+    }
+    assert_eq!(candidate.count_ties_between_nodes()?, 4);
+    assert_eq!(candidate.sum_tie_weights_between_nodes()?, 2.75);
+    let cliqueness: f32 = candidate.get_cliqueness()?;
+    assert_eq!(cliqueness, 2.75 / 4.0);
+    // core node 1 has weighted density 0.75, core node 2 has 0.625
+    assert_eq!(scorer.get_local_thresh_score(&candidate), 1.0);
+    let strict_scorer: DefaultScorer = DefaultScorer::new(1, alpha, Some(0.5), Some(0.7));
+    assert_eq!(strict_scorer.get_local_thresh_score(&candidate), 0.0);
+    let strict_scorer: DefaultScorer = DefaultScorer::new(1, alpha, Some(0.7), None);
+    assert_eq!(strict_scorer.score(&candidate)?, 0.0);
+    Ok(())
+}
+
+#[test]
+fn test_score_non_core_count() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published_at".into(), "conference".into()],
+        vec!["author".to_string(), "published_at".into(), "journal".into()],
+    ];
+    let graph_id: GraphId = 0.into();
+    let raw: Vec<String> = vec![
+        "0\t1\t2\tauthor\tpublished_at\tconference".to_string(),
+        "0\t1\t3\tauthor\tpublished_at\tconference".into(),
+        "0\t1\t4\tauthor\tpublished_at\tjournal".into(),
+    ];
+    let transformer: Transformer = gen_test_transformer(typespec, "author".to_string())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+
+    let scorer_type: ScorerType = "non_core_count".parse::<ScorerType>()?;
+    assert_eq!(scorer_type, ScorerType::NonCoreCount);
+    assert!("no_such_scorer".parse::<ScorerType>().is_err());
+    let scorer: Box<dyn Scorer<Graph>> = scorer_type.build(2, 1.0, Some(1.0), Some(1.0));
+    let default_scorer: Box<dyn Scorer<Graph>> =
+        ScorerType::Default.build(2, 1.0, Some(1.0), Some(1.0));
+
+    let mut candidate: Candidate<Graph> = Candidate::init_blank(&graph);
+    for &node_id in graph.core_ids.iter().chain(graph.non_core_ids.iter()) {
+        candidate.add_node(node_id)?;
+    }
+    // non-cores are counted regardless of their type
+    let expected_score: f32 = 2.0_f32.ln() + 4.0_f32.ln() + 1.0;
+    assert_eq!(scorer.score(&candidate)?, expected_score);
+    let expected_default_score: f32 = 2.0_f32.ln() + (3.0_f32.ln() + 2.0_f32.ln()) + 1.0;
+    assert_eq!(default_scorer.score(&candidate)?, expected_default_score);
+    Ok(())
+}
+
+#[test]
+fn test_score_size_constraints() -> CLQResult<()> {
+    let graph_id: GraphId = 0.into();
+    let raw: Vec<String> = vec![
+        "0\t1\t3\tauthor\tpublished_at\tconference".to_string(),
+        "0\t2\t3\tauthor\tpublished_at\tconference".into(),
+        "0\t1\t4\tauthor\tpublished_at\tconference".into(),
+        "0\t2\t4\tauthor\tpublished_at\tconference".into(),
+        "0\t1\t5\tauthor\tpublished_at\tjournal".into(),
+        "0\t2\t5\tauthor\tpublished_at\tjournal".into(),
+    ];
+    let mut transformer: Transformer = gen_test_transformer(gen_test_typespec(), "author".into())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let size_constraints: SizeConstraints = Transformer::process_size_constraints(
+        "{\"core\": [2, null], \"conference\": [0, 1]}",
+        &transformer.non_core_type_ids,
+    )?;
+    assert!(Transformer::process_size_constraints(
+        "{\"core\": [2, 1]}",
+        &transformer.non_core_type_ids
+    )
+    .is_err());
+    let scorer = DefaultScorer::new(2, 1.0, Some(1.0), Some(1.0))
+        .with_size_constraints(size_constraints.clone());
+
+    // a 1x1 clique is too small on the core side
+    let mut candidate: Candidate<Graph> = Candidate::new(1.into(), &graph, &scorer)?;
+    candidate.add_node(5.into())?;
+    let unconstrained_score: f32 = DefaultScorer::new(2, 1.0, Some(1.0), Some(1.0))
+        .score(&candidate)?;
+    assert_eq!(
+        scorer.score(&candidate)?,
+        -1.0 / (1.0 + unconstrained_score)
+    );
+    candidate.add_node(2.into())?;
+    assert!(scorer.score(&candidate)? > 0.0);
+    // at most one conference is allowed
+    candidate.add_node(3.into())?;
+    assert!(size_constraints.is_exceeded_by_adding(&candidate, 4.into())?);
+    candidate.remove_node(5.into())?;
+    assert!(!size_constraints.is_exceeded_by_adding(&candidate, 5.into())?);
+
+    // the search does not grow candidates past the maxima.
+    transformer.size_constraints = size_constraints;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    let res: Candidate<Graph> = transformer
+        .process_clique_rows::<TypedGraphBuilder, Graph>(
+            &graph,
+            Vec::new(),
+            graph_id,
+            false,
+            &mut output,
+        )?
+        .ok_or_else(CLQError::err_none)?
+        .top_candidate;
+    assert_eq!(res.core_ids.len(), 2);
+    assert_eq!(res.non_core_ids.len(), 2);
+    assert!(res.get_score()? > 0.0);
+    Ok(())
+}
+
+#[test]
+fn test_score_edge_type_constraints() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "published_at".into(), "conference".into()],
+        vec!["author".to_string(), "attended".into(), "conference".into()],
+        vec!["author".to_string(), "published_at".into(), "journal".into()],
+    ];
+    let graph_id: GraphId = 0.into();
+    let raw: Vec<String> = vec![
+        "0\t1\t3\tauthor\tpublished_at\tconference".to_string(),
+        "0\t2\t3\tauthor\tpublished_at\tconference".into(),
+        "0\t1\t4\tauthor\tpublished_at\tconference".into(),
+        "0\t2\t4\tauthor\tpublished_at\tconference".into(),
+        "0\t1\t5\tauthor\tpublished_at\tjournal".into(),
+        "0\t2\t5\tauthor\tpublished_at\tjournal".into(),
+        "0\t1\t3\tauthor\tattended\tconference".into(),
+        "0\t2\t3\tauthor\tattended\tconference".into(),
+        "0\t1\t4\tauthor\tattended\tconference".into(),
+    ];
+    let mut transformer: Transformer = gen_test_transformer(typespec, "author".into())?;
+    assert_eq!(transformer.relations.len(), 3);
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let edge_type_constraints = Transformer::process_edge_type_constraints(
+        "{\"attended\": 1.0}",
+        &transformer.edge_types,
+        &transformer.relations,
+    )?;
+    assert_eq!(edge_type_constraints.len(), 1);
+    assert!(Transformer::process_edge_type_constraints(
+        "{\"reviewed\": 1.0}",
+        &transformer.edge_types,
+        &transformer.relations,
+    )
+    .is_err());
+    assert!(Transformer::process_edge_type_constraints(
+        "{\"attended\": 1.5}",
+        &transformer.edge_types,
+        &transformer.relations,
+    )
+    .is_err());
+    let scorer = DefaultScorer::new(transformer.num_non_core_types, 1.0, None, None)
+        .with_edge_type_constraints(edge_type_constraints.clone());
+
+    // both authors attended conference 3
+    let mut candidate: Candidate<Graph> = Candidate::new(1.into(), &graph, &scorer)?;
+    for &node_id in &[2, 3, 5] {
+        candidate.add_node(node_id.into())?;
+    }
+    assert_eq!(
+        candidate.get_edge_type_densities(&transformer.relations)?,
+        vec![Some(1.0), Some(1.0), Some(1.0)]
+    );
+    assert!(scorer.score(&candidate)? > 0.0);
+    // only one of them attended conference 4
+    candidate.add_node(4.into())?;
+    assert_eq!(
+        candidate.get_edge_type_densities(&transformer.relations)?,
+        vec![Some(1.0), Some(0.75), Some(1.0)]
+    );
+    assert_eq!(scorer.score(&candidate)?, 0.0);
+    candidate.remove_node(5.into())?;
+    assert_eq!(
+        candidate.get_edge_type_densities(&transformer.relations)?[2],
+        None
+    );
+
+    // densities are appended to the output
+    transformer.edge_type_constraints = edge_type_constraints;
+    transformer.edge_type_densities = true;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.process_clique_rows::<TypedGraphBuilder, Graph>(
+        &graph,
+        Vec::new(),
+        graph_id,
+        false,
+        &mut output,
+    )?;
+    let line: String = String::from_utf8(buffer)?;
+    let densities: Vec<Option<f32>> =
+        serde_json::from_str(line.trim_end().split('\t').next_back().unwrap())?;
+    assert_eq!(densities.len(), 3);
+    assert_eq!(densities[1], Some(1.0));
+    Ok(())
+}
+
+#[test]
+fn test_score_time_window() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![vec![
+        "author".to_string(),
+        "published_at".into(),
+        "conference".into(),
+    ]];
+    let graph_id: GraphId = 0.into();
+    let raw: Vec<String> = vec![
+        "0\t1\t3\tauthor\tpublished_at\tconference\t\t100".to_string(),
+        "0\t2\t3\tauthor\tpublished_at\tconference\t\t110".into(),
+        "0\t1\t4\tauthor\tpublished_at\tconference\t\t120".into(),
+        "0\t2\t4\tauthor\tpublished_at\tconference\t1.0\t130".into(),
+        "0\t1\t5\tauthor\tpublished_at\tconference\t\t1000".into(),
+        "0\t2\t5\tauthor\tpublished_at\tconference\t\t1005".into(),
+    ];
+    let mut transformer: Transformer = gen_test_transformer(typespec, "author".into())?;
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, raw)?;
+    assert_eq!(rows[3].timestamp, Some(130));
+    assert!(transformer
+        .process_line("0\t1\t3\tauthor\tpublished_at\tconference\t\tyesterday".into())
+        .is_err());
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let time_window = TimeWindow {
+        max_span: Some(100),
+        span_penalty: 0.0,
+    };
+    let scorer = DefaultScorer::new(transformer.num_non_core_types, 1.0, None, None);
+    let windowed_scorer = DefaultScorer::new(transformer.num_non_core_types, 1.0, None, None)
+        .with_time_window(time_window);
+    let penalized_scorer = DefaultScorer::new(transformer.num_non_core_types, 1.0, None, None)
+        .with_time_window(TimeWindow {
+            max_span: None,
+            span_penalty: 0.001,
+        });
+
+    let mut candidate: Candidate<Graph> = Candidate::init_blank(&graph);
+    candidate.add_node(1.into())?;
+    assert_eq!(candidate.get_time_span(), None);
+    for &node_id in &[2, 3, 4] {
+        candidate.add_node(node_id.into())?;
+    }
+    assert_eq!(candidate.get_time_span(), Some((100, 130)));
+    assert!(windowed_scorer.score(&candidate)? > 0.0);
+    let penalty: f32 = scorer.score(&candidate)? - penalized_scorer.score(&candidate)?;
+    assert!((penalty - 0.03).abs() < 1e-5);
+    // penalties can at most zero out the score, and cannot be negative.
+    let heavily_penalized_scorer =
+        DefaultScorer::new(transformer.num_non_core_types, 1.0, None, None)
+            .with_time_window(TimeWindow::new(None, 1.0)?);
+    assert_eq!(heavily_penalized_scorer.score(&candidate)?, 0.0);
+    assert!(TimeWindow::new(None, -0.001).is_err());
+    assert!(TimeWindow::new(Some(-1), 0.0).is_err());
+    // conference 5 took place much later
+    candidate.add_node(5.into())?;
+    assert_eq!(candidate.get_time_span(), Some((100, 1005)));
+    assert!(scorer.score(&candidate)? > 0.0);
+    assert_eq!(windowed_scorer.score(&candidate)?, 0.0);
+    candidate.remove_node(5.into())?;
+    assert_eq!(candidate.get_time_span(), Some((100, 130)));
+    candidate.remove_node(3.into())?;
+    assert_eq!(candidate.get_time_span(), Some((120, 130)));
+
+    // the time span is appended to the output
+    transformer.time_window = time_window;
+    transformer.time_span_columns = true;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.process_clique_rows::<TypedGraphBuilder, Graph>(
+        &graph,
+        Vec::new(),
+        graph_id,
+        false,
+        &mut output,
+    )?;
+    let line: String = String::from_utf8(buffer)?;
+    let columns: Vec<&str> = line.trim_end().split('\t').collect();
+    assert_eq!(columns[3], "[1,2]");
+    assert_eq!(columns[4], "[3,4]");
+    assert_eq!(columns[columns.len() - 2..], ["100", "130"]);
+    Ok(())
+}
@@ -0,0 +1,130 @@
+/*
+ * Copyright (c) Facebook, Inc. and its affiliates.
+ *
+ * This source code is licensed under the MIT license found in the
+ * LICENSE file in the root directory of this source tree.
+ */
+extern crate lib_dachshund;
+
+use std::collections::HashMap;
+
+use rand::prelude::*;
+
+use lib_dachshund::dachshund::candidate::Candidate;
+use lib_dachshund::dachshund::error::{CLQError, CLQResult};
+use lib_dachshund::dachshund::graph::{Graph, TypedGraphBuilder};
+use lib_dachshund::dachshund::id_types::{GraphId, NodeId};
+use lib_dachshund::dachshund::output::Output;
+use lib_dachshund::dachshund::row::{CliqueRow, EdgeRow};
+use lib_dachshund::dachshund::significance::{NullModel, Significance};
+use lib_dachshund::dachshund::test_utils::{
+    gen_test_transformer, gen_test_transformer_from_args, gen_test_typespec, process_raw_vector,
+};
+use lib_dachshund::dachshund::transformer::Transformer;
+
+/// a 4x4 author x conference biclique (authors 1-4, conferences 101-104), plus 16 authors
+/// who each published at 2 of 16 other conferences and journals.
+fn gen_planted_clique() -> Vec<String> {
+    let mut raw: Vec<String> = Vec::new();
+    for author in 1..5 {
+        for conference in 101..105 {
+            raw.push(format!("0\t{}\t{}\tauthor\tpublished_at\tconference", author, conference));
+        }
+    }
+    for author in 5..21 {
+        for &target in &[130 + author % 16, 130 + (author + 5) % 16] {
+            let target_type = if target % 2 == 0 { "conference" } else { "journal" };
+            raw.push(format!("0\t{}\t{}\tauthor\tpublished_at\t{}", author, target, target_type));
+        }
+    }
+    raw
+}
+
+#[cfg(test)]
+#[test]
+fn test_rewire_preserves_degrees_and_types() -> CLQResult<()> {
+    let transformer: Transformer = gen_test_transformer(gen_test_typespec(), "author".into())?;
+    let mut rows: Vec<EdgeRow> = process_raw_vector(&transformer, gen_planted_clique())?;
+    let get_degrees = |rows: &[EdgeRow]| -> HashMap<(NodeId, usize), usize> {
+        let mut degrees: HashMap<(NodeId, usize), usize> = HashMap::new();
+        for row in rows {
+            *degrees.entry((row.source_id, row.target_type_id.value())).or_default() += 1;
+            *degrees.entry((row.target_id, row.target_type_id.value())).or_default() += 1;
+        }
+        degrees
+    };
+    let degrees = get_degrees(&rows);
+    let mut rng = StdRng::seed_from_u64(0);
+    let num_swaps: usize = 10 * rows.len();
+    let num_swapped: usize = NullModel::rewire(&mut rows, num_swaps, &mut rng);
+    assert!(num_swapped > 0);
+    assert_eq!(get_degrees(&rows), degrees);
+    Ok(())
+}
+
+#[test]
+fn test_significance_of_planted_clique() -> CLQResult<()> {
+    let graph_id: GraphId = 0.into();
+    let mut transformer: Transformer =
+        gen_test_transformer(gen_test_typespec(), "author".into())?;
+    transformer.global_thresh = None;
+    transformer.local_thresh = None;
+    transformer.null_model = Some(NullModel::new(19));
+    let rows: Vec<EdgeRow> = process_raw_vector(&transformer, gen_planted_clique())?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let core_type_id = *transformer.non_core_type_ids.require("author")?;
+    let conference_type_id = *transformer.non_core_type_ids.require("conference")?;
+    let mut clique_rows: Vec<CliqueRow> = Vec::new();
+    for author in 1..5 {
+        clique_rows.push(CliqueRow::new(graph_id, author, None));
+    }
+    for conference in 101..105 {
+        clique_rows.push(CliqueRow::new(graph_id, conference, Some(conference_type_id)));
+    }
+    let scorer = transformer
+        .scorer_type
+        .build::<Graph>(transformer.num_non_core_types, 1.0, None, None);
+    let candidate: Candidate<Graph> =
+        Candidate::from_clique_rows(clique_rows, &graph, scorer.as_ref())?
+            .ok_or_else(CLQError::err_none)?;
+    let significance: Significance =
+        transformer.get_significance::<TypedGraphBuilder, Graph>(&graph, &candidate, graph_id)?;
+    assert_eq!(significance.p_value, 0.05);
+    assert!(significance.z_score.ok_or_else(CLQError::err_none)? > 0.0);
+    // the same edge rows are recovered from the graph
+    assert_eq!(
+        NullModel::get_edge_rows(&graph, graph_id, core_type_id)?.len(),
+        rows.len()
+    );
+
+    // the p-value and z-score are appended to the output
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.process_clique_rows::<TypedGraphBuilder, Graph>(
+        &graph,
+        Vec::new(),
+        graph_id,
+        false,
+        &mut output,
+    )?;
+    let line: String = String::from_utf8(buffer)?;
+    let columns: Vec<&str> = line.trim_end().split('\t').collect();
+    assert!(columns[columns.len() - 2].parse::<f32>()? <= 0.1);
+    assert!(columns[columns.len() - 1].parse::<f32>()? > 0.0);
+    Ok(())
+}
+
+#[test]
+fn test_null_model_args() -> CLQResult<()> {
+    let typespec = r#"[["author", "published_at", "conference"]]"#;
+    let transformer = gen_test_transformer_from_args(typespec, &["--null_samples=10"])?;
+    assert_eq!(transformer.null_model.map(|x| x.num_samples), Some(10));
+    assert!(gen_test_transformer_from_args(typespec, &["--null_samples=0"]).is_err());
+    // ties between non-core nodes would not be rewired.
+    let typespec = r#"[["author", "published_at", "conference"],
+        ["conference", "located_in", "city"]]"#;
+    assert!(gen_test_transformer_from_args(typespec, &[]).is_ok());
+    assert!(gen_test_transformer_from_args(typespec, &["--null_samples=10"]).is_err());
+    Ok(())
+}
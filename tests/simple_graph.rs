@@ -5,14 +5,17 @@
  * LICENSE file in the root directory of this source tree.
  */
 extern crate lib_dachshund;
+use lib_dachshund::dachshund::error::CLQResult;
 use lib_dachshund::dachshund::graph::{
     SimpleUndirectedGraph, SimpleUndirectedGraphBuilder,
 };
 use lib_dachshund::dachshund::id_types::NodeId;
 use lib_dachshund::dachshund::input::Input;
+use lib_dachshund::dachshund::interner::{InternerScope, NodeIdInterner};
 use lib_dachshund::dachshund::output::Output;
 use lib_dachshund::dachshund::simple_transformer::{
-    GraphStatsTransformerBase, SimpleParallelTransformer, SimpleTransformer, TransformerBase,
+    GraphStatsTransformerBase, LineProcessor, SimpleParallelTransformer, SimpleTransformer,
+    TransformerBase,
 };
 use std::collections::{BTreeSet, HashSet};
 use std::iter::FromIterator;
@@ -250,3 +253,28 @@ fn test_parallel_transformer() {
     let expected_set = BTreeSet::from_iter(expected.split('\n'));
     assert_eq!(output_set, expected_set);
 }
+
+#[test]
+fn test_line_processor_with_string_node_ids() -> CLQResult<()> {
+    let line_processor = LineProcessor::new()
+        .with_node_id_interner(NodeIdInterner::new(InternerScope::PerGraph));
+    let process = |line: &str| -> CLQResult<(i64, i64)> {
+        let row = line_processor.process_line(line.to_string())?;
+        Ok(row.as_simple_edge_row().unwrap().as_tuple())
+    };
+    assert_eq!(process("g\talice@example.com\tbob@example.com")?, (0, 1));
+    assert_eq!(process("g\tbob@example.com\t10.0.0.1")?, (1, 2));
+    // ids are specific to each graph.
+    assert_eq!(process("h\t10.0.0.1\talice@example.com")?, (0, 1));
+    assert!(LineProcessor::new()
+        .process_line("g\talice@example.com\tbob@example.com".into())
+        .is_err());
+    // malformed lines are errors, rather than panics.
+    assert!(process("g\talice@example.com").is_err());
+    let mut buffer: Vec<u8> = Vec::new();
+    let text: &str = "g\t1\t2\ng\t1";
+    assert!(SimpleTransformer::new()
+        .run(Input::string(text.as_bytes()), Output::string(&mut buffer))
+        .is_err());
+    Ok(())
+}
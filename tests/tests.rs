@@ -6,17 +6,22 @@
  */
 extern crate lib_dachshund;
 
+use lib_dachshund::dachshund::beam::{BeamSearchResult, SearchBudget};
 use lib_dachshund::dachshund::candidate::Candidate;
 use lib_dachshund::dachshund::error::{CLQError, CLQResult};
 use lib_dachshund::dachshund::graph::{TypedGraphBuilder, Graph};
 use lib_dachshund::dachshund::id_types::{GraphId, NodeId};
+use lib_dachshund::dachshund::input::Input;
+use lib_dachshund::dachshund::interner::{InternerScope, NodeIdInterner};
 use lib_dachshund::dachshund::output::Output;
 use lib_dachshund::dachshund::row::{CliqueRow, EdgeRow};
 use lib_dachshund::dachshund::test_utils::{
     assert_nodes_have_ids, gen_single_clique, gen_test_transformer, gen_test_typespec,
     process_raw_vector,
 };
+use lib_dachshund::dachshund::trace::JsonLinesTraceSink;
 use lib_dachshund::dachshund::transformer::Transformer;
+use std::io::{ErrorKind, Write};
 
 #[cfg(test)]
 #[test]
@@ -69,6 +74,35 @@ fn test_process_single_line() -> CLQResult<()> {
     Ok(())
 }
 
+#[test]
+fn test_process_single_weighted_line() -> CLQResult<()> {
+    let ts = gen_test_typespec();
+    let transformer = gen_test_transformer(ts, "author".to_string())?;
+    let raw: String = "0\t1\t2\tauthor\tpublished_at\tjournal\t0.5".to_string();
+    let row: EdgeRow = transformer
+        .process_line(raw)?
+        .as_edge_row()
+        .ok_or_else(CLQError::err_none)?;
+    assert_eq!(row.weight, 0.5);
+    let raw: String = "0\t1\t2\tauthor\tpublished_at\tjournal".to_string();
+    let row: EdgeRow = transformer
+        .process_line(raw)?
+        .as_edge_row()
+        .ok_or_else(CLQError::err_none)?;
+    assert_eq!(row.weight, 1.0);
+    let raw: String = "0\t1\t2\tauthor\tpublished_at\tjournal\t1.5".to_string();
+    assert!(transformer.process_line(raw).is_err());
+    // malformed lines are errors, rather than panics.
+    assert!(transformer.process_line("0\t1\t2".into()).is_err());
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    let text: &str = "0\t1\t2\tauthor\tpublished_at\tjournal\n0\t1\t3";
+    assert!(transformer
+        .run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)
+        .is_err());
+    Ok(())
+}
+
 #[test]
 fn test_process_single_line_clique_row() -> CLQResult<()> {
     let ts = gen_test_typespec();
@@ -161,12 +195,19 @@ fn test_process_small_clique_with_non_clique_row() -> CLQResult<()> {
         "0\t2\t3\tauthor\tpublished_at\tconference".into(),
         "0\t1\t4\tauthor\tpublished_at\tconference".into(),
         "0\t2\t4\tauthor\tpublished_at\tconference".into(),
-        // nonsensical
-        "0\t2\t5\tconference\tpublished_at\tconference".into(),
     ];
     let graph_id: GraphId = 0.into();
 
     let transformer = gen_test_transformer(ts, "author".to_string())?;
+    // nonsensical: conference is not the source type of a relation between non-core types.
+    let error = transformer
+        .process_line("0\t2\t5\tconference\tpublished_at\tconference".into())
+        .err()
+        .ok_or_else(CLQError::err_none)?;
+    assert!(error.to_string().contains("conference"));
+    assert!(transformer
+        .process_line("0\t2\t5\tauthr\tpublished_at\tconference".into())
+        .is_err());
     let rows = process_raw_vector(&transformer, raw)?;
     let mut buffer: Vec<u8> = Vec::new();
     let mut output = Output::string(&mut buffer);
@@ -351,3 +392,429 @@ fn test_process_another_small_clique_with_two_kinds_of_rows() -> CLQResult<()> {
     assert_nodes_have_ids(&graph, &res.non_core_ids, vec![5], false);
     Ok(())
 }
+
+#[test]
+fn test_process_small_clique_with_drift() -> CLQResult<()> {
+    let raw = [
+        "0\t1\t3\tauthor\tpublished_at\tconference",
+        "0\t2\t3\tauthor\tpublished_at\tconference",
+        "0\t1\t4\tauthor\tpublished_at\tconference",
+        "0\t2\t4\tauthor\tpublished_at\tconference",
+        // prior clique: author 1 and conference 3 remain, author 9 is gone
+        "0\t1\tauthor\t\t\t",
+        "0\t9\tauthor\t\t\t",
+        "0\t3\tconference\t\t\t",
+    ];
+    let path = std::env::temp_dir().join(format!(
+        "dachshund_test_drift_{}.jsonl",
+        std::process::id()
+    ));
+    let run_with_drift = |interner: Option<NodeIdInterner>| -> CLQResult<serde_json::Value> {
+        let mut transformer = gen_test_transformer(gen_test_typespec(), "author".to_string())?;
+        transformer.node_id_interner = interner;
+        transformer.drift_sink = Some(Box::new(JsonLinesTraceSink::from_file(
+            path.to_str().ok_or_else(CLQError::err_none)?,
+        )?));
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut output = Output::string(&mut buffer);
+        transformer.run::<TypedGraphBuilder, Graph>(
+            Input::string(raw.join("\n").as_bytes()),
+            &mut output,
+        )?;
+        // flushes the drift file
+        drop(transformer);
+        let drift = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        std::fs::remove_file(&path)?;
+        Ok(drift)
+    };
+
+    let drift: serde_json::Value = run_with_drift(None)?;
+    assert_eq!(drift["graph_id"], 0);
+    assert_eq!(drift["clique_id"], serde_json::Value::Null);
+    assert_eq!(drift["joined"], serde_json::json!([2, 4]));
+    assert_eq!(drift["left"], serde_json::json!([9]));
+    assert!(drift["prior_score"].as_f64().ok_or_else(CLQError::err_none)? > 0.0);
+    let jaccard: f64 = drift["jaccard"].as_f64().ok_or_else(CLQError::err_none)?;
+    assert!((jaccard - 0.4).abs() < 1e-6);
+
+    // interned ids are mapped back to the original ones.
+    let drift: serde_json::Value =
+        run_with_drift(Some(NodeIdInterner::new(InternerScope::PerGraph)))?;
+    assert_eq!(drift["joined"], serde_json::json!(["2", "4"]));
+    assert_eq!(drift["left"], serde_json::json!(["9"]));
+    Ok(())
+}
+
+#[test]
+fn test_process_top_k_cliques() -> CLQResult<()> {
+    let ts = gen_test_typespec();
+    let mut raw: Vec<String> = Vec::new();
+    // a 3x3 clique and a 2x2 clique, sharing no nodes
+    for (core_ids, non_core_ids) in &[(vec![1, 2, 3], vec![10, 11, 12]), (vec![4, 5], vec![13, 14])] {
+        for core_id in core_ids {
+            for non_core_id in non_core_ids {
+                raw.push(format!(
+                    "0\t{}\t{}\tauthor\tpublished_at\tconference",
+                    core_id, non_core_id
+                ));
+            }
+        }
+    }
+    let graph_id: GraphId = 0.into();
+    let mut transformer = gen_test_transformer(ts, "author".to_string())?;
+    transformer.num_cliques = 3;
+    let rows = process_raw_vector(&transformer, raw.clone())?;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    let cliques: Vec<Vec<CliqueRow>> = transformer
+        .process_top_k_cliques::<TypedGraphBuilder, Graph>(
+            &rows,
+            Vec::new(),
+            graph_id,
+            false,
+            &mut output,
+        )?;
+    // the graph runs out of nodes before a third clique can be found
+    assert_eq!(cliques.len(), 2);
+    let ids: Vec<Vec<i64>> = cliques
+        .iter()
+        .map(|x| x.iter().map(|y| y.node_id.value()).collect())
+        .collect();
+    assert_eq!(ids[0], vec![1, 2, 3, 10, 11, 12]);
+    assert_eq!(ids[1], vec![4, 5, 13, 14]);
+
+    // cliques are tagged with their rank in the long format
+    transformer.long_format = true;
+    let text = raw.join("\n");
+    let input = Input::string(text.as_bytes());
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(input, &mut output)?;
+    let output_str: String = String::from_utf8(buffer)?;
+    let expected = vec![
+        "0\t0\t1\tauthor",
+        "0\t0\t2\tauthor",
+        "0\t0\t3\tauthor",
+        "0\t0\t10\tconference",
+        "0\t0\t11\tconference",
+        "0\t0\t12\tconference",
+        "0\t1\t4\tauthor",
+        "0\t1\t5\tauthor",
+        "0\t1\t13\tconference",
+        "0\t1\t14\tconference",
+    ];
+    assert_eq!(output_str, expected.join("\n") + "\n");
+    Ok(())
+}
+
+#[test]
+fn test_run_parallel() -> CLQResult<()> {
+    let ts = gen_test_typespec();
+    let non_core_types: Vec<String> = ts.iter().map(|x| x[2].clone()).collect();
+    let mut raw: Vec<String> = Vec::new();
+    // graphs of decreasing size, so that later graphs tend to finish first
+    for i in 0..8 {
+        let (_core_ids, _non_cores, clique_rows) = gen_single_clique(
+            i.into(),
+            10 - i as usize,
+            vec![3, 3],
+            non_core_types.clone(),
+            "author".to_string(),
+            vec!["published_at".to_string()],
+        );
+        raw.extend(clique_rows);
+    }
+    let text = raw.join("\n");
+    let mut transformer = gen_test_transformer(ts, "author".to_string())?;
+    transformer.debug = false;
+    // the wide format does not order non-core types deterministically
+    transformer.long_format = true;
+
+    let mut sequential_buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut sequential_buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)?;
+
+    transformer.num_threads = 4;
+    let mut parallel_buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut parallel_buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)?;
+
+    let output_str: String = String::from_utf8(parallel_buffer)?;
+    let graph_ids: Vec<&str> = output_str.lines().map(|x| &x[..1]).collect();
+    assert_eq!(graph_ids.first(), Some(&"0"));
+    assert_eq!(graph_ids.last(), Some(&"7"));
+    assert_eq!(output_str, String::from_utf8(sequential_buffer)?);
+
+    // the rows of each graph must be contiguous, e.g. so that interned ids are only
+    // released once a graph is complete.
+    let mut unsorted: Vec<String> = raw.clone();
+    unsorted.push(raw[0].clone());
+    let text = unsorted.join("\n");
+    transformer.node_id_interner = Some(NodeIdInterner::new(InternerScope::PerGraph));
+    for &num_threads in &[1, 4] {
+        transformer.num_threads = num_threads;
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut output = Output::string(&mut buffer);
+        let input = Input::string(text.as_bytes());
+        let result = transformer.run::<TypedGraphBuilder, Graph>(input, &mut output);
+        assert!(result.unwrap_err().to_string().contains("not contiguous"));
+    }
+    Ok(())
+}
+
+/// accepts a single line, and fails afterwards (e.g. like a closed pipe).
+struct SingleLineWriter {
+    num_lines: usize,
+}
+impl Write for SingleLineWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.num_lines > 0 {
+            return Err(std::io::Error::new(ErrorKind::BrokenPipe, "closed"));
+        }
+        self.num_lines += buf.iter().filter(|&&b| b == b'\n').count();
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_process_cliques_in_parallel_with_failing_output() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![vec!["user".to_string(), "uses".into(), "device".into()]];
+    let mut raw: Vec<String> = Vec::new();
+    // many more graphs than the workers can queue.
+    for graph_id in 0..100 {
+        for user in 1..3 {
+            for device in 11..13 {
+                raw.push(format!("{}\t{}\t{}\tuser\tuses\tdevice", graph_id, user, device));
+            }
+        }
+    }
+    let text = raw.join("\n");
+    let mut transformer = gen_test_transformer(typespec, "user".to_string())?;
+    transformer.debug = false;
+    transformer.num_threads = 2;
+    let mut writer = SingleLineWriter { num_lines: 0 };
+    let mut output = Output::writer(&mut writer);
+    let result =
+        transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output);
+    assert!(result.is_err());
+    assert_eq!(writer.num_lines, 1);
+    Ok(())
+}
+
+#[test]
+fn test_process_medium_clique_with_expansion_budget() -> CLQResult<()> {
+    let ts = gen_test_typespec();
+    let non_core_types = ts.iter().map(|x| x[2].clone()).collect();
+    let graph_id: GraphId = 0.into();
+    let (_core_ids, _non_cores, clique_rows) = gen_single_clique(
+        graph_id,
+        10,
+        vec![10, 10],
+        non_core_types,
+        "author".to_string(),
+        vec!["published_at".to_string()],
+    );
+    let mut transformer = gen_test_transformer(ts, "author".to_string())?;
+    transformer.debug = false;
+    transformer.search_budget = SearchBudget {
+        time: None,
+        num_expansions: Some(50),
+    };
+    let rows = process_raw_vector(&transformer, clique_rows.clone())?;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    let result: BeamSearchResult<Graph> = transformer
+        .process_clique_rows::<TypedGraphBuilder, Graph>(
+            &graph,
+            Vec::new(),
+            graph_id,
+            false,
+            &mut output,
+        )?
+        .ok_or_else(CLQError::err_none)?;
+    assert!(result.truncated);
+    // the best candidate found so far is still returned
+    assert!(result.top_candidate.get_score()? > 0.0);
+    assert!(result.top_candidate.core_ids.len() + result.top_candidate.non_core_ids.len() < 30);
+
+    // the output rows record whether the search was truncated
+    transformer.long_format = true;
+    let text = clique_rows.join("\n");
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)?;
+    let output_str: String = String::from_utf8(buffer)?;
+    assert!(output_str.lines().all(|x| x.ends_with("\ttrue")));
+
+    // and a large enough budget does not truncate the search
+    transformer.search_budget.num_expansions = Some(1_000_000);
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)?;
+    let output_str: String = String::from_utf8(buffer)?;
+    assert_eq!(output_str.lines().count(), 30);
+    assert!(output_str.lines().all(|x| x.ends_with("\tfalse")));
+    Ok(())
+}
+
+#[test]
+fn test_process_clique_with_non_core_relations() -> CLQResult<()> {
+    let ts: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "wrote".into(), "article".into()],
+        vec!["article".to_string(), "published_in".into(), "journal".into()],
+    ];
+    let raw = vec![
+        "0\t1\t11\tauthor\twrote\tarticle".to_string(),
+        "0\t2\t11\tauthor\twrote\tarticle".into(),
+        "0\t1\t12\tauthor\twrote\tarticle".into(),
+        "0\t2\t12\tauthor\twrote\tarticle".into(),
+        "0\t1\t13\tauthor\twrote\tarticle".into(),
+        "0\t2\t13\tauthor\twrote\tarticle".into(),
+        "0\t11\t21\tarticle\tpublished_in\tjournal".into(),
+        "0\t12\t21\tarticle\tpublished_in\tjournal".into(),
+        "0\t13\t21\tarticle\tpublished_in\tjournal".into(),
+        "0\t12\t22\tarticle\tpublished_in\tjournal".into(),
+    ];
+    let graph_id: GraphId = 0.into();
+    let transformer = gen_test_transformer(ts, "author".to_string())?;
+    assert_eq!(transformer.non_core_relations.len(), 1);
+    let rows = process_raw_vector(&transformer, raw)?;
+    let graph: Graph =
+        transformer.build_pruned_graph::<TypedGraphBuilder, Graph>(graph_id, &rows)?;
+    assert_eq!(graph.core_ids, vec![1.into(), 2.into()]);
+    assert_eq!(graph.non_core_ids.len(), 5);
+
+    // journal 22 only published one of the articles.
+    let scorer = transformer
+        .scorer_type
+        .build::<Graph>(transformer.num_non_core_types, 1.0, None, None);
+    let mut candidate: Candidate<Graph> = Candidate::new(1.into(), &graph, scorer.as_ref())?;
+    for &node_id in &[2, 11, 12, 21] {
+        candidate.add_node(node_id.into())?;
+    }
+    assert_eq!(candidate.get_typed_cliqueness(&transformer.non_core_relations)?, 1.0);
+    candidate.add_node(22.into())?;
+    assert_eq!(candidate.get_typed_cliqueness(&transformer.non_core_relations)?, 0.875);
+    assert_eq!(candidate.get_cliqueness()?, 1.0);
+    candidate.remove_node(21.into())?;
+    assert_eq!(candidate.get_typed_cliqueness(&transformer.non_core_relations)?, 5.0 / 6.0);
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    let res: Candidate<Graph> = transformer
+        .process_clique_rows::<TypedGraphBuilder, Graph>(
+            &graph,
+            Vec::new(),
+            graph_id,
+            false,
+            &mut output,
+        )?
+        .ok_or_else(CLQError::err_none)?
+        .top_candidate;
+    assert_nodes_have_ids(&graph, &res.core_ids, vec![1, 2], true);
+    assert_nodes_have_ids(&graph, &res.non_core_ids, vec![11, 12, 13, 21], false);
+
+    // relations must connect declared types.
+    let ts: Vec<Vec<String>> = vec![
+        vec!["author".to_string(), "wrote".into(), "article".into()],
+        vec!["venue".to_string(), "published_in".into(), "journal".into()],
+    ];
+    assert!(gen_test_transformer(ts, "author".to_string()).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_process_clique_with_string_node_ids() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![vec!["user".to_string(), "uses".into(), "device".into()]];
+    let mut raw: Vec<String> = Vec::new();
+    for graph_id in 0..2 {
+        for user in &["alice@example.com", "bob@example.com"] {
+            for device in &["007", "10.0.0.1"] {
+                raw.push(format!("{}\t{}\t{}\tuser\tuses\tdevice", graph_id, user, device));
+            }
+        }
+    }
+    let text = raw.join("\n");
+    let mut transformer = gen_test_transformer(typespec, "user".to_string())?;
+    transformer.debug = false;
+    assert!(transformer.process_line(raw[0].clone()).is_err());
+    transformer.node_id_interner = Some(NodeIdInterner::new(InternerScope::PerGraph));
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)?;
+    let output_str: String = String::from_utf8(buffer)?;
+    let lines: Vec<&str> = output_str.lines().collect();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let columns: Vec<&str> = line.split('\t').collect();
+        assert_eq!(columns[3], "[\"alice@example.com\",\"bob@example.com\"]");
+        assert_eq!(columns[4], "[\"007\",\"10.0.0.1\"]");
+    }
+
+    transformer.long_format = true;
+    transformer.num_threads = 2;
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)?;
+    let output_str: String = String::from_utf8(buffer)?;
+    let mut lines: Vec<&str> = output_str.lines().filter(|x| x.starts_with('1')).collect();
+    lines.sort();
+    assert_eq!(
+        lines,
+        vec![
+            "1\t007\tdevice",
+            "1\t10.0.0.1\tdevice",
+            "1\talice@example.com\tuser",
+            "1\tbob@example.com\tuser",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_process_clique_with_search_metadata() -> CLQResult<()> {
+    let typespec: Vec<Vec<String>> = vec![vec![
+        "author".to_string(),
+        "published".into(),
+        "article".into(),
+    ]];
+    let mut raw: Vec<String> = Vec::new();
+    for author in 1..4 {
+        for article in 11..13 {
+            raw.push(format!("0\t{}\t{}\tauthor\tpublished\tarticle", author, article));
+        }
+    }
+    // pruned away, along with article 13.
+    raw.push("0\t4\t13\tauthor\tpublished\tarticle".into());
+    let text = raw.join("\n");
+    let mut transformer = gen_test_transformer(typespec, "author".to_string())?;
+    transformer.debug = false;
+    transformer.min_degree = 2;
+    transformer.search_metadata = true;
+    transformer.long_format = true;
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut output = Output::string(&mut buffer);
+    transformer.run::<TypedGraphBuilder, Graph>(Input::string(text.as_bytes()), &mut output)?;
+    let output_str: String = String::from_utf8(buffer)?;
+    let lines: Vec<&str> = output_str.lines().collect();
+    assert_eq!(lines.len(), 5);
+    let columns: Vec<&str> = lines[0].split('\t').collect();
+    assert_eq!(columns.len(), 12);
+    assert!(columns[3].starts_with('{'));
+    // the long format does not otherwise print cliqueness.
+    assert_eq!(columns[4], "1");
+    assert!(columns[5].parse::<usize>()? > 0);
+    // the graph is small enough for the search to run out of candidates.
+    assert_eq!(columns[6], "all_candidates_visited");
+    // edges are counted in both directions.
+    assert_eq!(&columns[7..11], &["7", "14", "5", "12"]);
+    assert!(columns[11].parse::<u64>().is_ok());
+    Ok(())
+}